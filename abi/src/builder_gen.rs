@@ -0,0 +1,221 @@
+use std::collections::BTreeSet;
+
+use case::CaseExt;
+use everscale_types::abi::{AbiType, Function, NamedAbiType};
+use quote::{format_ident, quote};
+
+// integer fields which are safe to leave zeroed when building a call
+const DEFAULTABLE_INTEGER_FIELDS: [&str; 2] = ["call_id", "nonce"];
+
+pub struct BuilderGen;
+
+impl BuilderGen {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn collect_input_structs(&self, function: &Function, structs: &mut BTreeSet<String>) {
+        structs.insert(format!(
+            "{}FunctionInput",
+            function.name.as_ref().to_camel()
+        ));
+        collect_tuple_structs(&function.inputs, structs);
+    }
+
+    pub fn implement_builder(
+        &self,
+        struct_name: &str,
+        properties: &[NamedAbiType],
+        types: &[syn::Type],
+    ) -> proc_macro2::TokenStream {
+        if properties.is_empty() {
+            return proc_macro2::TokenStream::new();
+        }
+
+        let struct_name_ident = format_ident!("{}", struct_name);
+        let builder_name_ident = format_ident!("{}Builder", struct_name);
+
+        let fields: Vec<BuilderField> = properties
+            .iter()
+            .zip(types)
+            .map(|(property, ty)| BuilderField {
+                ident: format_ident!("{}", property.name.as_ref().to_snake()),
+                ty: ty.clone(),
+                optional: is_defaultable(property),
+            })
+            .collect();
+
+        // every required field gets its own type state: `()` while unset and `(T,)` once set
+        let required: Vec<&BuilderField> = fields.iter().filter(|x| !x.optional).collect();
+        let generics: Vec<syn::Ident> = (0..required.len())
+            .map(|i| format_ident!("T{}", i))
+            .collect();
+
+        let struct_fields: Vec<_> = fields
+            .iter()
+            .map(|field| {
+                let ident = &field.ident;
+                let ty = &field.ty;
+                match required.iter().position(|x| x.ident == field.ident) {
+                    Some(i) => {
+                        let generic = &generics[i];
+                        quote! { #ident: #generic, }
+                    }
+                    None => quote! { #ident: Option<#ty>, },
+                }
+            })
+            .collect();
+
+        let initial_fields: Vec<_> = fields
+            .iter()
+            .map(|field| {
+                let ident = &field.ident;
+                if field.optional {
+                    quote! { #ident: None, }
+                } else {
+                    quote! { #ident: (), }
+                }
+            })
+            .collect();
+        let unset_states = required.iter().map(|_| quote! { () });
+
+        let required_setters = required.iter().enumerate().map(|(i, field)| {
+            let ident = &field.ident;
+            let ty = &field.ty;
+
+            let other_generics: Vec<_> = generics
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(_, x)| x)
+                .collect();
+            let input_states = generics.iter().enumerate().map(|(j, x)| {
+                if j == i {
+                    quote! { () }
+                } else {
+                    quote! { #x }
+                }
+            });
+            let output_states = generics.iter().enumerate().map(|(j, x)| {
+                if j == i {
+                    quote! { (#ty,) }
+                } else {
+                    quote! { #x }
+                }
+            });
+            let moved_fields = fields.iter().map(|x| {
+                let other = &x.ident;
+                if other == ident {
+                    quote! { #ident: (#ident,), }
+                } else {
+                    quote! { #other: self.#other, }
+                }
+            });
+
+            quote! {
+                impl<#(#other_generics),*> #builder_name_ident<#(#input_states),*> {
+                    pub fn #ident(self, #ident: #ty) -> #builder_name_ident<#(#output_states),*> {
+                        #builder_name_ident {
+                            #(#moved_fields)*
+                        }
+                    }
+                }
+            }
+        });
+
+        let optional_setters: Vec<_> = fields
+            .iter()
+            .filter(|x| x.optional)
+            .map(|field| {
+                let ident = &field.ident;
+                let ty = &field.ty;
+                quote! {
+                    pub fn #ident(mut self, #ident: #ty) -> Self {
+                        self.#ident = Some(#ident);
+                        self
+                    }
+                }
+            })
+            .collect();
+
+        let optional_setters = if optional_setters.is_empty() {
+            proc_macro2::TokenStream::new()
+        } else {
+            quote! {
+                impl<#(#generics),*> #builder_name_ident<#(#generics),*> {
+                    #(#optional_setters)*
+                }
+            }
+        };
+
+        let set_states = required.iter().map(|x| {
+            let ty = &x.ty;
+            quote! { (#ty,) }
+        });
+        let built_fields = fields.iter().map(|field| {
+            let ident = &field.ident;
+            if field.optional {
+                quote! { #ident: self.#ident.unwrap_or_default(), }
+            } else {
+                quote! { #ident: self.#ident.0, }
+            }
+        });
+
+        quote! {
+            #[derive(Clone, Debug)]
+            pub struct #builder_name_ident<#(#generics),*> {
+                #(#struct_fields)*
+            }
+
+            impl #struct_name_ident {
+                pub fn builder() -> #builder_name_ident<#(#unset_states),*> {
+                    #builder_name_ident {
+                        #(#initial_fields)*
+                    }
+                }
+            }
+
+            #(#required_setters)*
+
+            #optional_setters
+
+            impl #builder_name_ident<#(#set_states),*> {
+                pub fn build(self) -> #struct_name_ident {
+                    #struct_name_ident {
+                        #(#built_fields)*
+                    }
+                }
+            }
+        }
+    }
+}
+
+struct BuilderField {
+    ident: syn::Ident,
+    ty: syn::Type,
+    optional: bool,
+}
+
+fn is_defaultable(property: &NamedAbiType) -> bool {
+    match &property.ty {
+        AbiType::Bool | AbiType::Optional(_) => true,
+        AbiType::Uint(_) | AbiType::Int(_) => {
+            DEFAULTABLE_INTEGER_FIELDS.contains(&property.name.as_ref().to_snake().as_str())
+        }
+        _ => false,
+    }
+}
+
+fn collect_tuple_structs(properties: &[NamedAbiType], structs: &mut BTreeSet<String>) {
+    for property in properties {
+        let mut ty = &property.ty;
+        while let AbiType::Ref(inner) = ty {
+            ty = inner.as_ref();
+        }
+
+        if let AbiType::Tuple(inner) = ty {
+            structs.insert(property.name.as_ref().to_camel());
+            collect_tuple_structs(inner, structs);
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use std::collections::BTreeSet;
+
+use case::CaseExt;
+use everscale_types::abi::Function;
+use quote::{format_ident, quote};
+
+use crate::function_gen::is_responsible;
+
+pub struct ClientGen;
+
+impl ClientGen {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Typed wrapper over `ExistingContract`: every function gets an internal message payload
+    /// builder, getters (functions with outputs not listed in `mutating`) are also run locally.
+    pub fn implement_client(
+        &self,
+        contract_name: &str,
+        functions: &[&Function],
+        mutating: &BTreeSet<String>,
+    ) -> proc_macro2::TokenStream {
+        let client_ident = format_ident!("{}", contract_name.to_camel());
+
+        let methods = functions.iter().map(|function| {
+            let name = function.name.as_ref();
+            let marker_ident = format_ident!("{}", name.to_camel());
+            let function_name_ident = format_ident!("{}", name.to_snake());
+            let input_ident = format_ident!("{}FunctionInput", name.to_camel());
+            let output_ident = format_ident!("{}FunctionOutput", name.to_camel());
+
+            let payload_ident = format_ident!("{}_payload", name.to_snake());
+            let payload = quote! {
+                pub fn #payload_ident(&self, input: &#input_ident) -> Result<everscale_types::prelude::Cell> {
+                    functions::#function_name_ident::encode_internal(input)
+                }
+            };
+
+            if function.outputs.is_empty() || mutating.contains(name) {
+                return payload;
+            }
+
+            let run_local_ident = if is_responsible(function) {
+                format_ident!("run_local_responsible")
+            } else {
+                format_ident!("run_local")
+            };
+
+            let run_local = if function.inputs.is_empty() {
+                quote! {
+                    pub fn #function_name_ident(&self) -> Result<#output_ident> {
+                        self.contract.#run_local_ident::<functions::#marker_ident>(#input_ident, &self.context)
+                    }
+                }
+            } else {
+                quote! {
+                    pub fn #function_name_ident(&self, input: #input_ident) -> Result<#output_ident> {
+                        self.contract.#run_local_ident::<functions::#marker_ident>(input, &self.context)
+                    }
+                }
+            };
+
+            quote! {
+                #payload
+                #run_local
+            }
+        });
+
+        quote! {
+            pub struct #client_ident {
+                pub contract: abi_codegen::existing_contract::ExistingContract,
+                pub context: abi_codegen::execution::ExecutionContext,
+            }
+
+            impl #client_ident {
+                pub fn new(contract: abi_codegen::existing_contract::ExistingContract) -> Self {
+                    Self {
+                        contract,
+                        context: Default::default(),
+                    }
+                }
+
+                pub fn with_context(mut self, context: abi_codegen::execution::ExecutionContext) -> Self {
+                    self.context = context;
+                    self
+                }
+
+                pub fn load(
+                    provider: &dyn abi_codegen::transport::ContractStateProvider,
+                    address: &everscale_types::models::message::StdAddr,
+                ) -> Result<Option<Self>> {
+                    Ok(provider.get_contract_state(address)?.map(Self::new))
+                }
+
+                #(#methods)*
+            }
+        }
+    }
+}
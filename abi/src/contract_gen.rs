@@ -0,0 +1,140 @@
+use case::CaseExt;
+use everscale_types::abi::{Contract, Event, Function};
+use quote::{format_ident, quote};
+
+use crate::make_abi_type;
+
+pub struct ContractGen;
+
+impl ContractGen {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Reassembles the whole contract ABI from the per-function and per-event descriptors.
+    pub fn implement_contract(
+        &self,
+        contract: &Contract,
+        functions: &[&Function],
+        events: &[&Event],
+    ) -> proc_macro2::TokenStream {
+        let function_idents = functions
+            .iter()
+            .map(|x| format_ident!("{}", x.name.as_ref().to_snake()));
+        let event_idents = events
+            .iter()
+            .map(|x| format_ident!("{}", x.name.as_ref().to_snake()));
+
+        let mut init_data: Vec<_> = contract.init_data.values().collect();
+        init_data.sort_by_key(|(key, _)| *key);
+
+        let init_data: Vec<_> = init_data
+            .into_iter()
+            .map(|(key, ty)| {
+                let ty = make_abi_type(ty.name.as_ref(), ty.ty.clone());
+                quote! { (#key, #ty) }
+            })
+            .collect();
+        let init_data_count = init_data.len();
+
+        let fields: Vec<_> = contract
+            .fields
+            .iter()
+            .map(|x| make_abi_type(x.name.as_ref(), x.ty.clone()))
+            .collect();
+        let fields_count = fields.len();
+
+        quote! {
+            pub fn contract() -> &'static everscale_types::abi::Contract {
+                static ONCE: std::sync::OnceLock<everscale_types::abi::Contract> = std::sync::OnceLock::new();
+
+                ONCE.get_or_init(|| {
+                    let function_descriptors = [ #(functions::#function_idents()),* ];
+                    let event_descriptors = [ #(events::#event_idents()),* ];
+                    let init_data: [(u64, NamedAbiType); #init_data_count] = [ #(#init_data),* ];
+                    let fields: [NamedAbiType; #fields_count] = [ #(#fields),* ];
+
+                    everscale_types::abi::Contract {
+                        abi_version: functions::ABI_VERSION,
+                        headers: std::sync::Arc::new(functions::HEADERS),
+                        functions: function_descriptors
+                            .into_iter()
+                            .map(|x| (x.name.clone(), x.clone()))
+                            .collect(),
+                        events: event_descriptors
+                            .into_iter()
+                            .map(|x| (x.name.clone(), x.clone()))
+                            .collect(),
+                        init_data: init_data
+                            .into_iter()
+                            .map(|(key, ty)| (ty.name.clone(), (key, ty)))
+                            .collect(),
+                        fields: std::sync::Arc::new(fields),
+                    }
+                })
+            }
+        }
+    }
+
+    /// Code hashes of the supported contract versions.
+    pub fn implement_code_hashes(&self, code_hashes: &[[u8; 32]]) -> proc_macro2::TokenStream {
+        let count = code_hashes.len();
+        let hashes = code_hashes.iter().map(|hash| {
+            quote! { everscale_types::prelude::HashBytes([#(#hash),*]) }
+        });
+
+        quote! {
+            pub const CODE_HASHES: [everscale_types::prelude::HashBytes; #count] = [ #(#hashes),* ];
+        }
+    }
+
+    /// Packing of the `data` section static variables and deploy address computation.
+    pub fn implement_init_data(&self) -> proc_macro2::TokenStream {
+        quote! {
+            impl InitData {
+                /// Packs the static variables and the public key into the contract data dictionary.
+                ///
+                /// Without a key the public key slot is left empty.
+                pub fn encode(
+                    &self,
+                    pubkey: Option<&ed25519_dalek::VerifyingKey>,
+                ) -> Result<everscale_types::prelude::Cell> {
+                    let tokens = Vec::<NamedAbiValue>::from(self.clone());
+                    let data = everscale_types::prelude::CellBuilder::build_from(
+                        everscale_types::dict::RawDict::<64>::new(),
+                    )?;
+                    contract().update_init_data(pubkey, &tokens, &data)
+                }
+
+                pub fn state_init(
+                    &self,
+                    code: everscale_types::prelude::Cell,
+                    pubkey: Option<&ed25519_dalek::VerifyingKey>,
+                ) -> Result<everscale_types::models::StateInit> {
+                    Ok(everscale_types::models::StateInit {
+                        split_depth: None,
+                        special: None,
+                        code: Some(code),
+                        data: Some(self.encode(pubkey)?),
+                        libraries: everscale_types::dict::Dict::new(),
+                    })
+                }
+
+                /// Computes the address of the contract deployed with this data and `code`.
+                pub fn compute_address(
+                    &self,
+                    workchain: i8,
+                    code: everscale_types::prelude::Cell,
+                    pubkey: Option<&ed25519_dalek::VerifyingKey>,
+                ) -> Result<everscale_types::models::message::StdAddr> {
+                    let state_init = self.state_init(code, pubkey)?;
+                    let cell = everscale_types::prelude::CellBuilder::build_from(&state_init)?;
+                    Ok(everscale_types::models::message::StdAddr::new(
+                        workchain,
+                        *cell.repr_hash(),
+                    ))
+                }
+            }
+        }
+    }
+}
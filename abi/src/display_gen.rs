@@ -0,0 +1,57 @@
+use case::CaseExt;
+use everscale_types::abi::NamedAbiType;
+use quote::{format_ident, quote};
+
+pub struct DisplayGen;
+
+impl DisplayGen {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Renders `call_name(a: 1, b: 2)` when `call_name` is provided and `{ a: 1, b: 2 }` otherwise.
+    pub fn implement_display(
+        &self,
+        struct_name: &str,
+        call_name: Option<&str>,
+        properties: &[NamedAbiType],
+    ) -> proc_macro2::TokenStream {
+        let struct_name_ident = format_ident!("{}", struct_name);
+
+        let (open, close) = match call_name {
+            Some(name) => (format!("{name}("), ")".to_string()),
+            None if properties.is_empty() => ("{".to_string(), "}".to_string()),
+            None => ("{ ".to_string(), " }".to_string()),
+        };
+
+        let fields = properties.iter().enumerate().map(|(i, property)| {
+            let label = if i == 0 {
+                format!("{}: ", property.name)
+            } else {
+                format!(", {}: ", property.name)
+            };
+            let ident = format_ident!("{}", property.name.as_ref().to_snake());
+
+            quote! {
+                f.write_str(#label)?;
+                abi_codegen::display::AbiDisplay::fmt_abi(&self.#ident, f)?;
+            }
+        });
+
+        quote! {
+            impl std::fmt::Display for #struct_name_ident {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    f.write_str(#open)?;
+                    #(#fields)*
+                    f.write_str(#close)
+                }
+            }
+
+            impl abi_codegen::display::AbiDisplay for #struct_name_ident {
+                fn fmt_abi(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    std::fmt::Display::fmt(self, f)
+                }
+            }
+        }
+    }
+}
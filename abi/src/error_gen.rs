@@ -0,0 +1,138 @@
+use case::CaseExt;
+use quote::{format_ident, quote};
+
+pub struct ContractError {
+    pub name: String,
+    pub code: i32,
+    pub message: String,
+}
+
+pub struct ErrorGen;
+
+impl ErrorGen {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Reads an error catalog: `[{ "name": "NotOwner", "code": 1001, "message": "..." }]`,
+    /// the message defaults to the name. Names and codes must be unique.
+    pub fn parse_errors(&self, content: &str) -> Result<Vec<ContractError>, String> {
+        let value = serde_json::from_str::<serde_json::Value>(content)
+            .map_err(|e| format!("Failed to parse errors: {e}"))?;
+        let Some(items) = value.as_array() else {
+            return Err("Expected errors as an array".to_string());
+        };
+
+        let mut errors: Vec<ContractError> = Vec::with_capacity(items.len());
+        for item in items {
+            let Some(name) = item.get("name").and_then(|x| x.as_str()) else {
+                return Err(format!("Error without a name: {item}"));
+            };
+            let Some(code) = item.get("code").and_then(|x| x.as_i64()) else {
+                return Err(format!("Error `{name}` without a code"));
+            };
+            let code = i32::try_from(code).map_err(|_| format!("Invalid code of `{name}`"))?;
+            let message = item.get("message").and_then(|x| x.as_str()).unwrap_or(name);
+
+            let name = name.to_camel();
+            if syn::parse_str::<syn::Ident>(&name).is_err() {
+                return Err(format!("Error name `{name}` is not a valid identifier"));
+            }
+            if let Some(other) = errors.iter().find(|x| x.name == name) {
+                return Err(format!("Duplicate error name `{}`", other.name));
+            }
+            if let Some(other) = errors.iter().find(|x| x.code == code) {
+                return Err(format!(
+                    "Code {code} is used by both `{}` and `{name}`",
+                    other.name
+                ));
+            }
+
+            errors.push(ContractError {
+                name,
+                code,
+                message: message.to_string(),
+            });
+        }
+
+        if errors.is_empty() {
+            return Err("Errors catalog is empty".to_string());
+        }
+
+        errors.sort_by_key(|x| x.code);
+        Ok(errors)
+    }
+
+    pub fn error_ident(&self, contract_name: &str) -> syn::Ident {
+        format_ident!("{}Error", contract_name.to_camel())
+    }
+
+    /// Contract error codes thrown by `require`/`revert`, mapped from TVM exit codes.
+    pub fn implement_errors(
+        &self,
+        contract_name: &str,
+        errors: &[ContractError],
+    ) -> proc_macro2::TokenStream {
+        let error_ident = self.error_ident(contract_name);
+
+        let variants = errors.iter().map(|error| {
+            let variant_ident = format_ident!("{}", error.name);
+            let doc = &error.message;
+            quote! {
+                #[doc = #doc]
+                #variant_ident,
+            }
+        });
+        let from_code_arms = errors.iter().map(|error| {
+            let variant_ident = format_ident!("{}", error.name);
+            let code = error.code;
+            quote! { #code => Some(Self::#variant_ident), }
+        });
+        let code_arms = errors.iter().map(|error| {
+            let variant_ident = format_ident!("{}", error.name);
+            let code = error.code;
+            quote! { Self::#variant_ident => #code, }
+        });
+        let message_arms = errors.iter().map(|error| {
+            let variant_ident = format_ident!("{}", error.name);
+            let message = &error.message;
+            quote! { Self::#variant_ident => #message, }
+        });
+
+        quote! {
+            #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+            pub enum #error_ident {
+                #(#variants)*
+            }
+
+            impl #error_ident {
+                pub fn from_exit_code(exit_code: i32) -> Option<Self> {
+                    match exit_code {
+                        #(#from_code_arms)*
+                        _ => None,
+                    }
+                }
+
+                pub fn exit_code(&self) -> i32 {
+                    match self {
+                        #(#code_arms)*
+                    }
+                }
+
+                pub fn message(&self) -> &'static str {
+                    match self {
+                        #(#message_arms)*
+                    }
+                }
+            }
+
+            impl std::fmt::Display for #error_ident {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    write!(f, "{} (exit code {})", self.message(), self.exit_code())
+                }
+            }
+
+            impl std::error::Error for #error_ident {}
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use case::CaseExt;
+use everscale_types::abi::Event;
+use quote::{format_ident, quote};
+
+use crate::make_abi_type;
+
+pub struct EventGen;
+
+impl EventGen {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn implement_id(&self, event: &Event) -> proc_macro2::TokenStream {
+        let id_ident = event_id_ident(event.name.as_ref());
+        let id = event.id;
+
+        quote! {
+            pub const #id_ident: u32 = #id;
+        }
+    }
+
+    pub fn implement_descriptor(&self, event: &Event) -> proc_macro2::TokenStream {
+        let name = event.name.as_ref();
+        let event_name_ident = format_ident!("{}", name.to_snake());
+
+        let inputs: Vec<_> = event
+            .inputs
+            .iter()
+            .map(|x| make_abi_type(x.name.as_ref(), x.ty.clone()))
+            .collect();
+        let inputs_count = inputs.len();
+
+        quote! {
+            pub fn #event_name_ident() -> &'static everscale_types::abi::Event {
+                static ONCE: std::sync::OnceLock<everscale_types::abi::Event> = std::sync::OnceLock::new();
+
+                ONCE.get_or_init(|| {
+                    let inputs: [NamedAbiType; #inputs_count] = [ #(#inputs),* ];
+
+                    everscale_types::abi::EventBuilder::new(functions::ABI_VERSION, #name)
+                    .with_inputs(inputs)
+                    .build()
+                })
+            }
+        }
+    }
+
+    /// Marker type tying the event descriptor to its input struct.
+    pub fn implement_marker(&self, name: &str) -> proc_macro2::TokenStream {
+        let marker_ident = format_ident!("{}", name.to_camel());
+        let event_name_ident = format_ident!("{}", name.to_snake());
+        let input_ident = format_ident!("{}EventInput", name.to_camel());
+
+        quote! {
+            #[derive(Clone, Copy, Debug)]
+            pub struct #marker_ident;
+
+            impl abi_codegen::existing_contract::EventDescr for #marker_ident {
+                type Input = #input_ident;
+
+                fn event() -> &'static everscale_types::abi::Event {
+                    #event_name_ident()
+                }
+            }
+        }
+    }
+
+    pub fn implement_helpers(&self, name: &str) -> proc_macro2::TokenStream {
+        let event_name_ident = format_ident!("{}", name.to_snake());
+        let input_ident = format_ident!("{}EventInput", name.to_camel());
+
+        quote! {
+            pub mod #event_name_ident {
+                use super::*;
+
+                /// Decodes an external outbound message body: event id followed by the packed arguments.
+                pub fn decode(body: &everscale_types::prelude::CellSlice<'_>) -> Result<#input_ident> {
+                    super::#event_name_ident()
+                        .decode_internal_input(body.clone())?
+                        .try_into()
+                }
+            }
+        }
+    }
+
+    pub fn implement_event_enum(&self, events: &[&Event]) -> proc_macro2::TokenStream {
+        let variants = events.iter().map(|event| {
+            let variant_ident = format_ident!("{}", event.name.as_ref().to_camel());
+            let input_ident = format_ident!("{}EventInput", event.name.as_ref().to_camel());
+            quote! { #variant_ident(#input_ident), }
+        });
+
+        let decoders = events.iter().map(|event| {
+            let variant_ident = format_ident!("{}", event.name.as_ref().to_camel());
+            let event_name_ident = format_ident!("{}", event.name.as_ref().to_snake());
+            let id_ident = event_id_ident(event.name.as_ref());
+            quote! {
+                events::#id_ident => Event::#variant_ident(events::#event_name_ident::decode(body)?),
+            }
+        });
+
+        let display_arms = events.iter().map(|event| {
+            let variant_ident = format_ident!("{}", event.name.as_ref().to_camel());
+            quote! { Event::#variant_ident(input) => std::fmt::Display::fmt(input, f), }
+        });
+
+        quote! {
+            #[derive(Clone, Debug, PartialEq)]
+            pub enum Event {
+                #(#variants)*
+            }
+
+            impl Event {
+                /// Decodes an event body, dispatching on its event id.
+                pub fn decode(body: &everscale_types::prelude::CellSlice<'_>) -> Result<Self> {
+                    match Self::try_decode(body)? {
+                        Some(event) => Ok(event),
+                        None => Err(anyhow::anyhow!("Unknown event id: {:#010x}", body.get_u32(0)?)),
+                    }
+                }
+
+                /// Same as `decode`, but returns `None` for ids which are not events of this contract.
+                pub fn try_decode(body: &everscale_types::prelude::CellSlice<'_>) -> Result<Option<Self>> {
+                    let event = match body.get_u32(0)? {
+                        #(#decoders)*
+                        _ => return Ok(None),
+                    };
+                    Ok(Some(event))
+                }
+
+                /// Decodes all events emitted by a transaction, in order, together with their logical time.
+                pub fn from_transaction(
+                    transaction: &everscale_types::models::Transaction,
+                ) -> Result<Vec<(u64, Self)>> {
+                    let mut events = Vec::new();
+
+                    for message in transaction.out_msgs.values() {
+                        let message = message?;
+                        let message = message.parse::<everscale_types::models::Message<'_>>()?;
+
+                        let everscale_types::models::MsgInfo::ExtOut(info) = &message.info else {
+                            continue;
+                        };
+
+                        if let Some(event) = Self::try_decode(&message.body)? {
+                            events.push((info.created_lt, event));
+                        }
+                    }
+
+                    Ok(events)
+                }
+            }
+
+            impl std::fmt::Display for Event {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    match self {
+                        #(#display_arms)*
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub fn event_id_ident(name: &str) -> syn::Ident {
+    format_ident!("{}_ID", name.to_snake().to_uppercase())
+}
@@ -0,0 +1,476 @@
+use case::CaseExt;
+use everscale_types::abi::{AbiHeaderType, Function};
+use quote::{format_ident, quote};
+
+const ANSWER_ID_INPUT: &str = "answerId";
+
+pub struct FunctionGen;
+
+impl FunctionGen {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn implement_helpers(&self, name: &str) -> proc_macro2::TokenStream {
+        let function_name_ident = format_ident!("{}", name.to_snake());
+        let input_ident = format_ident!("{}FunctionInput", name.to_camel());
+        let output_ident = format_ident!("{}FunctionOutput", name.to_camel());
+
+        quote! {
+            pub mod #function_name_ident {
+                use super::*;
+
+                /// Encodes an internal message body: function id followed by the packed arguments.
+                pub fn encode_internal(input: &#input_ident) -> Result<everscale_types::prelude::Cell> {
+                    let tokens = Vec::<NamedAbiValue>::from(input.clone());
+                    let body = super::#function_name_ident().encode_internal_input(&tokens)?;
+                    Ok(body.build()?)
+                }
+
+                /// Builds an unsigned external message body.
+                ///
+                /// `UnsignedBody::hash` is the data to sign, the signature can then be
+                /// attached with `UnsignedBody::with_signature`. `pubkey` is the key the body
+                /// will be signed with, it fills an unset `pubkey` header.
+                pub fn prepare_external(
+                    input: &#input_ident,
+                    headers: &Headers,
+                    pubkey: Option<&ed25519_dalek::VerifyingKey>,
+                ) -> Result<everscale_types::abi::UnsignedBody> {
+                    let tokens = Vec::<NamedAbiValue>::from(input.clone());
+                    let external = super::#function_name_ident().encode_external(&tokens);
+                    headers.with_pubkey(pubkey).apply(external).build_input()
+                }
+
+                /// Encodes an external message body, signed when the key is provided.
+                pub fn encode_external(
+                    input: &#input_ident,
+                    headers: &Headers,
+                    key: Option<&ed25519_dalek::SigningKey>,
+                ) -> Result<everscale_types::prelude::Cell> {
+                    let pubkey = key.map(ed25519_dalek::SigningKey::verifying_key);
+                    let body = prepare_external(input, headers, pubkey.as_ref())?;
+                    match key {
+                        Some(key) => body.sign(key, None),
+                        None => body.without_signature(),
+                    }
+                }
+
+                /// Builds an external message to `address`, signed when the key is provided.
+                pub fn encode_external_message(
+                    input: &#input_ident,
+                    headers: &Headers,
+                    address: &everscale_types::models::message::StdAddr,
+                    key: Option<&ed25519_dalek::SigningKey>,
+                ) -> Result<everscale_types::models::OwnedMessage> {
+                    let tokens = Vec::<NamedAbiValue>::from(input.clone());
+                    let external = super::#function_name_ident().encode_external(&tokens);
+                    let pubkey = key.map(ed25519_dalek::SigningKey::verifying_key);
+                    let headers = headers.with_pubkey(pubkey.as_ref());
+                    let message = headers.apply(external).build_message(address)?;
+                    match key {
+                        Some(key) => message.sign(key, None),
+                        None => message.without_signature(),
+                    }
+                }
+
+                /// Decodes an internal message body.
+                pub fn decode_input(
+                    body: &everscale_types::prelude::CellSlice<'_>,
+                ) -> Result<#input_ident> {
+                    super::#function_name_ident()
+                        .decode_internal_input(body.clone())?
+                        .try_into()
+                }
+
+                /// Decodes an external message body.
+                pub fn decode_external_input(
+                    body: &everscale_types::prelude::CellSlice<'_>,
+                ) -> Result<#input_ident> {
+                    super::#function_name_ident()
+                        .decode_external_input(body.clone())?
+                        .try_into()
+                }
+
+                /// Encodes the function result: output id followed by the packed values.
+                pub fn encode_output(output: &#output_ident) -> Result<everscale_types::prelude::Cell> {
+                    let function = super::#function_name_ident();
+                    let tokens = Vec::<NamedAbiValue>::from(output.clone());
+                    let body = everscale_types::abi::Function::encode_internal_msg_body(
+                        function.abi_version,
+                        function.output_id,
+                        &tokens,
+                    )?;
+                    Ok(body.build()?)
+                }
+
+                /// Decodes an external outbound message body with the function result.
+                pub fn decode_output(
+                    body: &everscale_types::prelude::CellSlice<'_>,
+                ) -> Result<#output_ident> {
+                    super::#function_name_ident()
+                        .decode_output(body.clone())?
+                        .try_into()
+                }
+
+                /// Decodes a responsible answer: the callback id followed by the function result.
+                pub fn decode_responsible_output(
+                    body: &everscale_types::prelude::CellSlice<'_>,
+                ) -> Result<#output_ident> {
+                    let function = super::#function_name_ident();
+                    let mut slice = body.clone();
+                    slice.skip_first(32, 0)?;
+                    NamedAbiValue::load_tuple(&function.outputs, function.abi_version, &mut slice)?
+                        .try_into()
+                }
+            }
+        }
+    }
+
+    pub fn implement_ids(&self, function: &Function) -> proc_macro2::TokenStream {
+        let name = function.name.as_ref();
+        let input_id_ident = input_id_ident(name);
+        let output_id_ident = output_id_ident(name);
+        let input_id = function.input_id;
+        let output_id = function.output_id;
+
+        quote! {
+            pub const #input_id_ident: u32 = #input_id;
+            pub const #output_id_ident: u32 = #output_id;
+        }
+    }
+
+    /// Marker type tying the function descriptor to its input and output structs.
+    pub fn implement_marker(
+        &self,
+        name: &str,
+        error_ident: Option<&syn::Ident>,
+        pinned: bool,
+    ) -> proc_macro2::TokenStream {
+        let marker_ident = format_ident!("{}", name.to_camel());
+        let function_name_ident = format_ident!("{}", name.to_snake());
+        let input_ident = format_ident!("{}FunctionInput", name.to_camel());
+        let output_ident = format_ident!("{}FunctionOutput", name.to_camel());
+
+        let error = error_ident.map(|error_ident| {
+            quote! {
+                fn error(exit_code: i32) -> Option<anyhow::Error> {
+                    #error_ident::from_exit_code(exit_code).map(Into::into)
+                }
+            }
+        });
+        let code_hashes = pinned.then(|| {
+            quote! {
+                fn code_hashes() -> &'static [everscale_types::prelude::HashBytes] {
+                    &CODE_HASHES
+                }
+            }
+        });
+
+        quote! {
+            #[derive(Clone, Copy, Debug)]
+            pub struct #marker_ident;
+
+            impl abi_codegen::existing_contract::FunctionDescr for #marker_ident {
+                type Input = #input_ident;
+                type Output = #output_ident;
+
+                fn function() -> &'static everscale_types::abi::Function {
+                    #function_name_ident()
+                }
+
+                fn contract() -> &'static everscale_types::abi::Contract {
+                    contract()
+                }
+
+                #error
+
+                #code_hashes
+            }
+        }
+    }
+
+    pub fn implement_call_enum(&self, functions: &[&Function]) -> proc_macro2::TokenStream {
+        let variants = functions.iter().map(|function| {
+            let variant_ident = format_ident!("{}", function.name.as_ref().to_camel());
+            let input_ident = format_ident!("{}FunctionInput", function.name.as_ref().to_camel());
+            quote! { #variant_ident(#input_ident), }
+        });
+
+        let decoders = functions.iter().map(|function| {
+            let variant_ident = format_ident!("{}", function.name.as_ref().to_camel());
+            let function_name_ident = format_ident!("{}", function.name.as_ref().to_snake());
+            let input_id_ident = input_id_ident(function.name.as_ref());
+            quote! {
+                functions::#input_id_ident => Ok(Call::#variant_ident(if internal {
+                    functions::#function_name_ident::decode_input(body)?
+                } else {
+                    functions::#function_name_ident::decode_external_input(body)?
+                })),
+            }
+        });
+
+        let display_arms = functions.iter().map(|function| {
+            let variant_ident = format_ident!("{}", function.name.as_ref().to_camel());
+            quote! { Call::#variant_ident(input) => std::fmt::Display::fmt(input, f), }
+        });
+
+        quote! {
+            #[derive(Clone, Debug, PartialEq)]
+            pub enum Call {
+                #(#variants)*
+            }
+
+            impl Call {
+                /// Decodes a message body of any function, dispatching on its input id.
+                pub fn decode(body: &everscale_types::prelude::CellSlice<'_>, internal: bool) -> Result<Self> {
+                    match functions::read_function_id(body, internal)? {
+                        #(#decoders)*
+                        id => Err(anyhow::anyhow!("Unknown function input id: {:#010x}", id)),
+                    }
+                }
+            }
+
+            impl std::fmt::Display for Call {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    match self {
+                        #(#display_arms)*
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn implement_output_enum(&self, functions: &[&Function]) -> proc_macro2::TokenStream {
+        let variants = functions.iter().map(|function| {
+            let variant_ident = format_ident!("{}", function.name.as_ref().to_camel());
+            let output_ident = format_ident!("{}FunctionOutput", function.name.as_ref().to_camel());
+            quote! { #variant_ident(#output_ident), }
+        });
+
+        let decoders = functions.iter().map(|function| {
+            let variant_ident = format_ident!("{}", function.name.as_ref().to_camel());
+            let function_name_ident = format_ident!("{}", function.name.as_ref().to_snake());
+            let output_id_ident = output_id_ident(function.name.as_ref());
+            quote! {
+                functions::#output_id_ident => Ok(Output::#variant_ident(
+                    functions::#function_name_ident::decode_output(body)?
+                )),
+            }
+        });
+
+        let responsible_decoders = functions
+            .iter()
+            .filter(|function| is_responsible(function))
+            .map(|function| {
+                let variant_ident = format_ident!("{}", function.name.as_ref().to_camel());
+                let function_name_ident = format_ident!("{}", function.name.as_ref().to_snake());
+                quote! {
+                    if let Ok(output) = functions::#function_name_ident::decode_responsible_output(body) {
+                        return Ok(Output::#variant_ident(output));
+                    }
+                }
+            });
+
+        quote! {
+            #[derive(Clone, Debug, PartialEq)]
+            pub enum Output {
+                #(#variants)*
+            }
+
+            impl Output {
+                /// Decodes an external outbound message body of any function, dispatching on its output id.
+                pub fn decode(body: &everscale_types::prelude::CellSlice<'_>) -> Result<Self> {
+                    match functions::read_function_id(body, true)? {
+                        #(#decoders)*
+                        id => Err(anyhow::anyhow!("Unknown function output id: {:#010x}", id)),
+                    }
+                }
+
+                /// Decodes a responsible answer of any responsible function.
+                ///
+                /// Answers start with the caller's callback id rather than a function id, so the
+                /// outputs of responsible functions are tried in order and the first one which
+                /// decodes wins. Prefer `decode_responsible_output` of the called function when
+                /// it is known, as functions with the same output types can't be told apart.
+                pub fn decode_responsible(body: &everscale_types::prelude::CellSlice<'_>) -> Result<Self> {
+                    #(#responsible_decoders)*
+                    Err(anyhow::anyhow!("No responsible function output matches the answer"))
+                }
+            }
+        }
+    }
+
+    /// Server-side counterpart of the contract: one method per function and a dispatcher
+    /// which drives them with encoded message bodies.
+    pub fn implement_handler(
+        &self,
+        contract_name: &str,
+        functions: &[&Function],
+    ) -> proc_macro2::TokenStream {
+        let handler_ident = format_ident!("{}Handler", contract_name.to_camel());
+
+        let methods = functions.iter().map(|function| {
+            let name = function.name.as_ref();
+            let method_ident = format_ident!("{}", name.to_snake());
+            let input_ident = format_ident!("{}FunctionInput", name.to_camel());
+            let output_ident = format_ident!("{}FunctionOutput", name.to_camel());
+            let message = format!("`{name}` is not implemented");
+            quote! {
+                fn #method_ident(&mut self, input: #input_ident) -> Result<#output_ident> {
+                    let _ = input;
+                    Err(anyhow::anyhow!(#message))
+                }
+            }
+        });
+
+        let dispatch_arms = functions.iter().map(|function| {
+            let name = function.name.as_ref();
+            let variant_ident = format_ident!("{}", name.to_camel());
+            let function_name_ident = format_ident!("{}", name.to_snake());
+            quote! {
+                Call::#variant_ident(input) => {
+                    functions::#function_name_ident::encode_output(&self.#function_name_ident(input)?)
+                }
+            }
+        });
+
+        quote! {
+            pub trait #handler_ident {
+                #(#methods)*
+
+                /// Decodes the message body, calls the matching method and encodes its result.
+                fn dispatch(
+                    &mut self,
+                    body: &everscale_types::prelude::CellSlice<'_>,
+                    internal: bool,
+                ) -> Result<everscale_types::prelude::Cell> {
+                    match Call::decode(body, internal)? {
+                        #(#dispatch_arms)*
+                    }
+                }
+            }
+        }
+    }
+
+    /// Contract-level header helpers: the function id reader for external bodies and the
+    /// `Headers` values, unset ones are filled by `ExternalInput` defaults.
+    pub fn implement_headers(&self, headers: &[AbiHeaderType]) -> proc_macro2::TokenStream {
+        let (fields, setters): (Vec<_>, Vec<_>) = headers
+            .iter()
+            .map(|header| match header {
+                AbiHeaderType::Time => (
+                    quote! {
+                        /// Message creation time in milliseconds.
+                        pub time: Option<u64>,
+                    },
+                    quote! {
+                        if let Some(time) = self.time {
+                            input = input.set_time(time);
+                        }
+                    },
+                ),
+                AbiHeaderType::Expire => (
+                    quote! {
+                        /// Message expiration unix time in seconds.
+                        pub expire: Option<u32>,
+                    },
+                    quote! {
+                        if let Some(expire) = self.expire {
+                            input = input.set_expire_at(expire);
+                        }
+                    },
+                ),
+                AbiHeaderType::PublicKey => (
+                    quote! {
+                        /// Signer public key, taken from the signing key when unset.
+                        pub pubkey: Option<ed25519_dalek::VerifyingKey>,
+                    },
+                    quote! {
+                        if let Some(pubkey) = &self.pubkey {
+                            input = input.set_pubkey(pubkey);
+                        }
+                    },
+                ),
+            })
+            .unzip();
+
+        let with_pubkey = if headers
+            .iter()
+            .any(|x| matches!(x, AbiHeaderType::PublicKey))
+        {
+            quote! {
+                let mut headers = self.clone();
+                if headers.pubkey.is_none() {
+                    headers.pubkey = pubkey.copied();
+                }
+                headers
+            }
+        } else {
+            quote! {
+                let _ = pubkey;
+                self.clone()
+            }
+        };
+
+        let header_skips = headers.iter().map(|header| match header {
+            AbiHeaderType::Time => quote! { slice.skip_first(64, 0)?; },
+            AbiHeaderType::Expire => quote! { slice.skip_first(32, 0)?; },
+            AbiHeaderType::PublicKey => quote! {
+                if slice.load_bit()? {
+                    slice.skip_first(256, 0)?;
+                }
+            },
+        });
+
+        quote! {
+            /// Reads the function id of a message body, skipping the signature and headers of external ones.
+            pub fn read_function_id(
+                body: &everscale_types::prelude::CellSlice<'_>,
+                internal: bool,
+            ) -> Result<u32> {
+                let mut slice = body.clone();
+                if !internal {
+                    if slice.load_bit()? {
+                        slice.skip_first(512, 0)?;
+                    }
+                    #(#header_skips)*
+                }
+                Ok(slice.load_u32()?)
+            }
+
+            #[derive(Clone, Debug, Default)]
+            pub struct Headers {
+                #(#fields)*
+            }
+
+            impl Headers {
+                //the pubkey header must match the key the message is signed with
+                fn with_pubkey(&self, pubkey: Option<&ed25519_dalek::VerifyingKey>) -> Self {
+                    #with_pubkey
+                }
+
+                fn apply<'f, 'a>(
+                    &'a self,
+                    mut input: everscale_types::abi::ExternalInput<'f, 'a>,
+                ) -> everscale_types::abi::ExternalInput<'f, 'a> {
+                    #(#setters)*
+                    input
+                }
+            }
+        }
+    }
+}
+
+/// Responsible functions take the callback id as their first input.
+pub fn is_responsible(function: &Function) -> bool {
+    matches!(function.inputs.first(), Some(input) if input.name.as_ref() == ANSWER_ID_INPUT)
+}
+
+pub fn input_id_ident(name: &str) -> syn::Ident {
+    format_ident!("{}_INPUT_ID", name.to_snake().to_uppercase())
+}
+
+pub fn output_id_ident(name: &str) -> syn::Ident {
+    format_ident!("{}_OUTPUT_ID", name.to_snake().to_uppercase())
+}
@@ -1,34 +1,109 @@
 extern crate proc_macro;
 
+use std::collections::BTreeSet;
 use std::fs;
 use std::sync::Arc;
 
 use case::CaseExt;
 use everscale_types::abi::{
-    AbiHeaderType, AbiType, Contract, Function, NamedAbiType, PlainAbiType,
+    AbiHeaderType, AbiType, Contract, Event, Function, NamedAbiType, PlainAbiType,
 };
 use proc_macro::TokenStream;
 use quote::{format_ident, quote};
 use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
 use syn::Result;
 use syn::{parse_macro_input, ItemMod};
 
 use crate::models::FunctionDescriptionTokens;
 
+mod builder_gen;
+mod client_gen;
+mod contract_gen;
+mod display_gen;
+mod error_gen;
+mod event_gen;
+mod function_gen;
 mod models;
+mod test_gen;
 mod trait_impl_gen;
 
 struct ModuleParams {
     path: String,
+    //emit proptest round-trip tests for every generated struct
+    proptest: bool,
+    //json catalog of contract error codes, see `ErrorGen::parse_errors`
+    errors: Option<syn::LitStr>,
+    //accepted code hashes, checked before typed local runs
+    code_hashes: Vec<[u8; 32]>,
+    //functions with outputs that change the state, the client gets no run methods for them
+    mutating: Vec<syn::LitStr>,
 }
 
 impl Parse for ModuleParams {
     fn parse(input: ParseStream) -> Result<Self> {
         let path = input.parse::<syn::LitStr>()?.value();
-        Ok(ModuleParams { path })
+        let mut params = ModuleParams {
+            path,
+            proptest: false,
+            errors: None,
+            code_hashes: Vec::new(),
+            mutating: Vec::new(),
+        };
+
+        while input.parse::<Option<syn::Token![,]>>()?.is_some() {
+            if input.is_empty() {
+                break;
+            }
+
+            let ident = input.parse::<syn::Ident>()?;
+            match ident.to_string().as_str() {
+                "proptest" => params.proptest = true,
+                "errors" => {
+                    input.parse::<syn::Token![=]>()?;
+                    params.errors = Some(input.parse::<syn::LitStr>()?);
+                }
+                "code_hashes" => {
+                    let content;
+                    syn::parenthesized!(content in input);
+                    let hashes =
+                        Punctuated::<syn::LitStr, syn::Token![,]>::parse_terminated(&content)?;
+                    for hash in hashes {
+                        let Some(bytes) = parse_code_hash(&hash.value()) else {
+                            return Err(syn::Error::new(hash.span(), "Invalid code hash"));
+                        };
+                        params.code_hashes.push(bytes);
+                    }
+                }
+                "mutating" => {
+                    let content;
+                    syn::parenthesized!(content in input);
+                    let names =
+                        Punctuated::<syn::LitStr, syn::Token![,]>::parse_terminated(&content)?;
+                    params.mutating.extend(names);
+                }
+                _ => return Err(syn::Error::new(ident.span(), "Unknown abi parameter")),
+            }
+        }
+
+        Ok(params)
     }
 }
 
+// hex encoded 256-bit hash
+fn parse_code_hash(value: &str) -> Option<[u8; 32]> {
+    let value = value.trim();
+    if value.len() != 64 || !value.bytes().all(|x| x.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let mut hash = [0u8; 32];
+    for (i, byte) in hash.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(value.get(i * 2..i * 2 + 2)?, 16).ok()?;
+    }
+    Some(hash)
+}
+
 #[proc_macro_attribute]
 pub fn abi(params: TokenStream, input: TokenStream) -> TokenStream {
     let mut generated_structs: Vec<proc_macro2::TokenStream> = Vec::new();
@@ -47,7 +122,27 @@ pub fn abi(params: TokenStream, input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as ItemMod);
     let mod_name = &input.ident;
 
+    let error_gen = error_gen::ErrorGen::new();
+    let errors = match &params.errors {
+        Some(path) => {
+            let full_path = std::env::current_dir().unwrap().join(path.value());
+            let errors = fs::read_to_string(&full_path)
+                .map_err(|e| format!("Failed to read {}: {e}", full_path.display()))
+                .and_then(|content| error_gen.parse_errors(&content));
+
+            match errors {
+                Ok(errors) => Some(errors),
+                Err(e) => return syn::Error::new(path.span(), e).to_compile_error().into(),
+            }
+        }
+        None => None,
+    };
+    let error_ident = errors
+        .as_ref()
+        .map(|_| error_gen.error_ident(&mod_name.to_string()));
+
     let mut struct_gen = StructGen::new();
+    let function_gen = function_gen::FunctionGen::new();
 
     contract.functions.iter().for_each(|(name, function)| {
         let name = name.to_string();
@@ -59,12 +154,28 @@ pub fn abi(params: TokenStream, input: TokenStream) -> TokenStream {
         } = struct_gen.process_function(name, function);
 
         generated_functions.push(body);
+        generated_functions.push(function_gen.implement_ids(function));
+        generated_functions.push(function_gen.implement_marker(
+            function.name.as_ref(),
+            error_ident.as_ref(),
+            !params.code_hashes.is_empty(),
+        ));
+        generated_functions.push(function_gen.implement_helpers(function.name.as_ref()));
 
         generated_structs.push(input);
         generated_structs.push(output);
         generated_structs.extend_from_slice(&inner_models.as_slice());
     });
 
+    let mut events: Vec<&Event> = contract.events.values().collect();
+    events.sort_by(|a, b| a.name.cmp(&b.name));
+
+    for event in events.iter() {
+        generated_structs.extend(struct_gen.process_event(event));
+    }
+
+    generated_structs.extend(struct_gen.process_init_data(&contract));
+
     let trait_gen = trait_impl_gen::TraitImplGen::new();
 
     let mut trait_implementations: Vec<proc_macro2::TokenStream> = Vec::new();
@@ -74,26 +185,88 @@ pub fn abi(params: TokenStream, input: TokenStream) -> TokenStream {
         trait_implementations.push(struct_traits)
     }
 
-    // let structs: Vec<_> = contract
-    //     .events
-    //     .iter()
-    //     .map(|(name, event)| {
-    //         let name = name.to_string();
-    //         let name = format_ident!("{}EventInput", name);
-    //         let field: Vec<_> = event
-    //             .inputs
-    //             .iter()
-    //             .map(|f| {
-    //                 let property = make_struct_property(Some(f.name.to_string()), &f.ty);
-    //                 let name_ident = format_ident!("{}", f.name.to_string());
-    //                 let ty_ident = property.type_name();
-    //                 quote! {
-    //                     pub #name_ident: #ty_ident,
-    //                 }
-    //             })
-    //             .collect();
-    //     })
-    //     .collect();
+    let display_gen = display_gen::DisplayGen::new();
+
+    let call_names: std::collections::HashMap<String, &str> = contract
+        .functions
+        .keys()
+        .map(|name| (format!("{}FunctionInput", name.to_camel()), name.as_ref()))
+        .chain(
+            contract
+                .events
+                .keys()
+                .map(|name| (format!("{}EventInput", name.to_camel()), name.as_ref())),
+        )
+        .collect();
+
+    let mut display_implementations: Vec<proc_macro2::TokenStream> = Vec::new();
+
+    for (name, properties) in struct_gen.generated_structs.iter() {
+        let call_name = call_names.get(name).copied();
+        display_implementations.push(display_gen.implement_display(name, call_name, properties));
+    }
+
+    let builder_gen = builder_gen::BuilderGen::new();
+
+    let mut builder_structs = BTreeSet::new();
+    for function in contract.functions.values() {
+        builder_gen.collect_input_structs(function, &mut builder_structs);
+    }
+
+    let mut builders: Vec<proc_macro2::TokenStream> = Vec::new();
+
+    for name in builder_structs.iter() {
+        let (Some(properties), Some(types)) = (
+            struct_gen.generated_structs.get(name),
+            struct_gen.generated_struct_types.get(name),
+        ) else {
+            continue;
+        };
+        builders.push(builder_gen.implement_builder(name, properties, types));
+    }
+
+    let mut functions: Vec<&Function> = contract.functions.values().collect();
+    functions.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let event_gen = event_gen::EventGen::new();
+
+    let event_ids: Vec<_> = events.iter().map(|x| event_gen.implement_id(x)).collect();
+    let event_descriptors: Vec<_> = events
+        .iter()
+        .map(|x| event_gen.implement_descriptor(x))
+        .collect();
+    let event_helpers: Vec<_> = events
+        .iter()
+        .map(|x| {
+            let marker = event_gen.implement_marker(x.name.as_ref());
+            let helpers = event_gen.implement_helpers(x.name.as_ref());
+            quote! {
+                #marker
+                #helpers
+            }
+        })
+        .collect();
+    let event_enum = event_gen.implement_event_enum(&events);
+
+    let contract_gen = contract_gen::ContractGen::new();
+    let contract_descriptor = contract_gen.implement_contract(&contract, &functions, &events);
+    let init_data = contract_gen.implement_init_data();
+    let code_hashes = if params.code_hashes.is_empty() {
+        proc_macro2::TokenStream::new()
+    } else {
+        contract_gen.implement_code_hashes(&params.code_hashes)
+    };
+
+    let test_gen = test_gen::TestGen::new();
+    let ids_test = test_gen.implement_ids_test(&functions, &events);
+    let tests = if params.proptest {
+        let mut structs: Vec<_> = struct_gen.generated_structs.iter().collect();
+        structs.sort_by(|a, b| a.0.cmp(b.0));
+
+        test_gen.implement_tests(&structs, &struct_gen.generated_struct_types)
+    } else {
+        proc_macro2::TokenStream::new()
+    };
 
     let header_type: syn::Type = syn::parse_str("everscale_types::abi::AbiHeaderType").unwrap();
     let abi_type: syn::Type = syn::parse_str("everscale_types::abi::AbiVersion").unwrap();
@@ -116,6 +289,28 @@ pub fn abi(params: TokenStream, input: TokenStream) -> TokenStream {
         [ #(#header_idents),* ]
     };
 
+    let call_enum = function_gen.implement_call_enum(&functions);
+    let output_enum = function_gen.implement_output_enum(&functions);
+    let handler_trait = function_gen.implement_handler(&mod_name.to_string(), &functions);
+    if let Some(name) = params
+        .mutating
+        .iter()
+        .find(|x| !contract.functions.contains_key(x.value().as_str()))
+    {
+        return syn::Error::new(name.span(), format!("Unknown function {}", name.value()))
+            .to_compile_error()
+            .into();
+    }
+    let mutating: BTreeSet<String> = params.mutating.iter().map(|x| x.value()).collect();
+    let client =
+        client_gen::ClientGen::new().implement_client(&mod_name.to_string(), &functions, &mutating);
+
+    let contract_errors = match &errors {
+        Some(errors) => error_gen.implement_errors(&mod_name.to_string(), errors),
+        None => proc_macro2::TokenStream::new(),
+    };
+
+    let headers_struct = function_gen.implement_headers(&contract.headers);
     let header_count = contract.headers.len();
     let major = contract.abi_version.major;
     let minor = contract.abi_version.minor;
@@ -134,14 +329,52 @@ pub fn abi(params: TokenStream, input: TokenStream) -> TokenStream {
 
             #(#trait_implementations)*
 
+            #(#builders)*
+
+            #(#display_implementations)*
+
             pub mod functions {
                 use super::*;
 
-                const HEADERS: [#header_type; #header_count] = #slice_token;
-                const ABI_VERSION: #abi_type = <#abi_type>::new(#major, #minor);
+                pub const HEADERS: [#header_type; #header_count] = #slice_token;
+                pub const ABI_VERSION: #abi_type = <#abi_type>::new(#major, #minor);
+
+                #headers_struct
 
                 #(#generated_functions)*
             }
+
+            pub mod events {
+                use super::*;
+
+                #(#event_ids)*
+
+                #(#event_descriptors)*
+
+                #(#event_helpers)*
+            }
+
+            #event_enum
+
+            #contract_descriptor
+
+            #init_data
+
+            #code_hashes
+
+            #contract_errors
+
+            #call_enum
+
+            #output_enum
+
+            #handler_trait
+
+            #client
+
+            #ids_test
+
+            #tests
         }
     };
 
@@ -151,6 +384,9 @@ pub fn abi(params: TokenStream, input: TokenStream) -> TokenStream {
 struct StructGen {
     generated_structs: std::collections::HashMap<String, Vec<NamedAbiType>>,
 
+    //rust types of the generated struct fields, in declaration order
+    generated_struct_types: std::collections::HashMap<String, Vec<syn::Type>>,
+
     unique_tokes: std::collections::HashMap<AbiType, StructProperty>,
 
     //used only for one function
@@ -164,6 +400,7 @@ impl StructGen {
             temporary_internal_structs_idents: Vec::new(),
 
             generated_structs: std::collections::HashMap::new(),
+            generated_struct_types: std::collections::HashMap::new(),
         }
     }
 
@@ -273,6 +510,7 @@ impl StructGen {
         let mut properties = Vec::<proc_macro2::TokenStream>::new();
 
         let mut inner_fields = Vec::new();
+        let mut types = Vec::new();
 
         let function_tuple = AbiType::Tuple(values.clone());
 
@@ -297,8 +535,13 @@ impl StructGen {
             };
 
             properties.push(quote.into());
+            types.push(ty_ident);
         }
 
+        self.generated_struct_types
+            .entry(name.to_string())
+            .or_insert(types);
+
         if !inner_fields.is_empty() {
             self.unique_tokes.insert(
                 function_tuple.clone(),
@@ -311,11 +554,12 @@ impl StructGen {
 
         let body = if properties.is_empty() {
             quote! {
-                type #struct_name_ident = ();
+                #[derive(Clone, Debug, PartialEq)]
+                pub struct #struct_name_ident;
             }
         } else {
             quote! {
-                #[derive(Clone, Debug)]
+                #[derive(Clone, Debug, PartialEq)]
                 pub struct #struct_name_ident {
                     #(#properties)*
                 }
@@ -349,6 +593,38 @@ impl StructGen {
         model.into()
     }
 
+    fn process_event(&mut self, event: &Event) -> Vec<proc_macro2::TokenStream> {
+        let struct_name = format!("{}EventInput", event.name.as_ref().to_camel());
+        self.process_struct(struct_name, event.inputs.clone())
+    }
+
+    fn process_init_data(&mut self, contract: &Contract) -> Vec<proc_macro2::TokenStream> {
+        let mut init_data: Vec<_> = contract.init_data.values().collect();
+        init_data.sort_by_key(|(key, _)| *key);
+
+        let properties: Arc<[NamedAbiType]> =
+            init_data.into_iter().map(|(_, x)| x.clone()).collect();
+        self.process_struct("InitData".to_string(), properties)
+    }
+
+    //generates a standalone struct together with its internal structs
+    fn process_struct(
+        &mut self,
+        struct_name: String,
+        properties: Arc<[NamedAbiType]>,
+    ) -> Vec<proc_macro2::TokenStream> {
+        let model = self.generate_model(&struct_name, properties.clone());
+
+        if !self.generated_structs.contains_key(&struct_name) {
+            self.generated_structs
+                .insert(struct_name, properties.to_vec());
+        }
+
+        let mut models = vec![model];
+        models.append(&mut self.temporary_internal_structs_idents);
+        models
+    }
+
     fn make_struct_property_with_internal(
         &mut self,
         initial_name: String,
@@ -425,6 +701,7 @@ impl StructGen {
                 }
 
                 let mut internal_properties: Vec<proc_macro2::TokenStream> = Vec::new();
+                let mut internal_types: Vec<syn::Type> = Vec::new();
 
                 for p in &structs {
                     let p_name = p.name();
@@ -434,18 +711,20 @@ impl StructGen {
                         pub #rust_property_name_ident: #internal_ident,
                     };
                     internal_properties.push(quote);
+                    internal_types.push(internal_ident);
                 }
 
                 let internal_struct = if !internal_properties.is_empty() {
                     quote! {
-                        #[derive(Clone, Debug)]
+                        #[derive(Clone, Debug, PartialEq)]
                         pub struct #struct_name_ident {
                             #(#internal_properties)*
                         }
                     }
                 } else {
                     quote! {
-                        type #struct_name_ident = ();
+                        #[derive(Clone, Debug, PartialEq)]
+                        pub struct #struct_name_ident;
                     }
                 };
 
@@ -459,6 +738,10 @@ impl StructGen {
                 {
                     self.unique_tokes.insert(param.clone(), property.clone());
 
+                    self.generated_struct_types
+                        .entry(camel_case_struct_name.clone())
+                        .or_insert(internal_types);
+
                     if !self.generated_structs.contains_key(&camel_case_struct_name) {
                         self.generated_structs
                             .insert(camel_case_struct_name, a.to_vec());
@@ -617,13 +900,13 @@ fn quote_abi_type(ty: &AbiType) -> proc_macro2::TokenStream {
         AbiType::VarInt(value) => {
             let val = value.get();
             quote! {
-                everscale_types::abi::AbiType::Int(core::num::nonzero::NonZeroU8(#val))
+                everscale_types::abi::AbiType::VarInt(core::num::NonZeroU8::new(#val).unwrap())
             }
         }
         AbiType::VarUint(value) => {
             let val = value.get();
             quote! {
-                everscale_types::abi:AbiType::Uint(core::num::nonzero::NonZeroU8(#val))
+                everscale_types::abi::AbiType::VarUint(core::num::NonZeroU8::new(#val).unwrap())
             }
         }
         AbiType::Tuple(tuple) => {
@@ -647,7 +930,7 @@ fn quote_abi_type(ty: &AbiType) -> proc_macro2::TokenStream {
         AbiType::FixedArray(ty, size) => {
             let ty = quote_abi_type(&ty);
             quote! {
-                everscale_types::abi:AbiType::FixedArray(std::sync::Arc<#ty>, #size)
+                everscale_types::abi::AbiType::FixedArray(std::sync::Arc::new(#ty), #size)
             }
         }
         AbiType::Map(key, value) => {
@@ -680,16 +963,16 @@ fn quote_abi_type(ty: &AbiType) -> proc_macro2::TokenStream {
             let value_type = quote_abi_type(&value);
             syn::parse_quote!(everscale_types::abi::AbiType::Map(#key_type, std::sync::Arc::new(#value_type)))
         }
-        AbiType::Optional(_) => {
-            let ty = quote_abi_type(ty);
+        AbiType::Optional(ty) => {
+            let ty = quote_abi_type(&ty);
             quote! {
-                everscale_types::abi::AbiType::Optional(std::sync::Arc<#ty>)
+                everscale_types::abi::AbiType::Optional(std::sync::Arc::new(#ty))
             }
         }
-        AbiType::Ref(_) => {
-            let ty = quote_abi_type(ty);
+        AbiType::Ref(ty) => {
+            let ty = quote_abi_type(&ty);
             quote! {
-                everscale_types::abi::AbiType::Ref(std::sync::Arc<#ty>)
+                everscale_types::abi::AbiType::Ref(std::sync::Arc::new(#ty))
             }
         }
     };
@@ -0,0 +1,265 @@
+use std::collections::HashMap;
+
+use case::CaseExt;
+use everscale_types::abi::{AbiType, Event, Function, NamedAbiType};
+use quote::{format_ident, quote};
+
+use crate::event_gen::event_id_ident;
+use crate::function_gen::{input_id_ident, output_id_ident};
+
+// proptest implements `Strategy` only for tuples of up to 12 elements
+const MAX_STRATEGY_TUPLE_LEN: usize = 12;
+
+pub struct TestGen;
+
+impl TestGen {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn implement_tests(
+        &self,
+        structs: &[(&String, &Vec<NamedAbiType>)],
+        struct_types: &HashMap<String, Vec<syn::Type>>,
+    ) -> proc_macro2::TokenStream {
+        let strategies: Vec<_> = structs
+            .iter()
+            .map(|(name, properties)| {
+                let types = struct_types
+                    .get(name.as_str())
+                    .expect("Struct field types are not generated");
+                self.implement_strategy(name, properties, types)
+            })
+            .collect();
+
+        let round_trip_tests: Vec<_> = structs
+            .iter()
+            .map(|(name, _)| self.implement_round_trip_test(name))
+            .collect();
+
+        quote! {
+            #[cfg(test)]
+            mod abi_tests {
+                use super::*;
+                use proptest::prelude::*;
+
+                #(#strategies)*
+
+                proptest! {
+                    #(#round_trip_tests)*
+                }
+            }
+        }
+    }
+
+    /// Checks the generated id constants against the runtime descriptors, emitted regardless
+    /// of the `proptest` flag.
+    pub fn implement_ids_test(
+        &self,
+        functions: &[&Function],
+        events: &[&Event],
+    ) -> proc_macro2::TokenStream {
+        let function_asserts = functions.iter().map(|function| {
+            let name = function.name.as_ref();
+            let function_name_ident = format_ident!("{}", name.to_snake());
+            let input_id_ident = input_id_ident(name);
+            let output_id_ident = output_id_ident(name);
+            quote! {
+                assert_eq!(functions::#input_id_ident, functions::#function_name_ident().input_id);
+                assert_eq!(functions::#output_id_ident, functions::#function_name_ident().output_id);
+            }
+        });
+
+        let event_asserts = events.iter().map(|event| {
+            let name = event.name.as_ref();
+            let event_name_ident = format_ident!("{}", name.to_snake());
+            let id_ident = event_id_ident(name);
+            quote! {
+                assert_eq!(events::#id_ident, events::#event_name_ident().id);
+            }
+        });
+
+        quote! {
+            #[cfg(test)]
+            mod abi_ids_tests {
+                use super::*;
+
+                #[test]
+                fn ids_match_runtime_descriptors() {
+                    #(#function_asserts)*
+                    #(#event_asserts)*
+                }
+            }
+        }
+    }
+
+    fn implement_strategy(
+        &self,
+        struct_name: &str,
+        properties: &[NamedAbiType],
+        types: &[syn::Type],
+    ) -> proc_macro2::TokenStream {
+        let struct_name_ident = format_ident!("{}", struct_name);
+        let strategy_ident = strategy_ident(struct_name);
+
+        if properties.is_empty() {
+            return quote! {
+                fn #strategy_ident() -> impl Strategy<Value = #struct_name_ident> {
+                    Just(#struct_name_ident)
+                }
+            };
+        }
+
+        let idents: Vec<_> = properties
+            .iter()
+            .map(|x| format_ident!("{}", x.name.as_ref().to_snake()))
+            .collect();
+
+        let strategies: Vec<_> = properties
+            .iter()
+            .zip(types)
+            .map(|(property, ty)| quote_strategy(&property.ty, ty))
+            .collect();
+        let strategy_chunks: Vec<_> = strategies
+            .chunks(MAX_STRATEGY_TUPLE_LEN)
+            .map(|chunk| quote! { (#(#chunk,)*) })
+            .collect();
+
+        let pattern_chunks: Vec<_> = idents
+            .chunks(MAX_STRATEGY_TUPLE_LEN)
+            .map(|chunk| quote! { (#(#chunk,)*) })
+            .collect();
+
+        quote! {
+            fn #strategy_ident() -> impl Strategy<Value = #struct_name_ident> {
+                (#(#strategy_chunks,)*).prop_map(|(#(#pattern_chunks,)*)| #struct_name_ident {
+                    #(#idents),*
+                })
+            }
+        }
+    }
+
+    fn implement_round_trip_test(&self, struct_name: &str) -> proc_macro2::TokenStream {
+        let struct_name_ident = format_ident!("{}", struct_name);
+        let strategy_ident = strategy_ident(struct_name);
+        let test_ident = format_ident!("{}_round_trip", struct_name.to_snake());
+
+        quote! {
+            #[test]
+            fn #test_ident(value in #strategy_ident()) {
+                let abi_value = value.as_abi();
+                prop_assert!(abi_value.has_type(&<#struct_name_ident as WithAbiType>::abi_type()));
+                prop_assert_eq!(<#struct_name_ident as FromAbi>::from_abi(abi_value).unwrap(), value);
+            }
+        }
+    }
+}
+
+fn strategy_ident(struct_name: &str) -> syn::Ident {
+    format_ident!("{}_strategy", struct_name.to_snake())
+}
+
+// mirrors the rust types picked by `StructGen::make_struct_property`, nested structs are
+// taken from the generated field type since tuples of the same shape share one struct
+fn quote_strategy(ty: &AbiType, rust_ty: &syn::Type) -> proc_macro2::TokenStream {
+    match ty {
+        AbiType::Uint(bits) => match bits {
+            8 => quote! { any::<u8>() },
+            16 => quote! { any::<u16>() },
+            32 => quote! { any::<u32>() },
+            64 => quote! { any::<u64>() },
+            128 => quote! { any::<u128>() },
+            160 => quote! { any::<[u8; 20]>() },
+            256 => quote! { any::<[u8; 32]>().prop_map(everscale_types::prelude::HashBytes) },
+            _ => {
+                let shift = 64u16.saturating_sub(*bits) as u32;
+                quote! { any::<u64>().prop_map(|x| num_bigint::BigUint::from(x >> #shift)) }
+            }
+        },
+        AbiType::Int(bits) => match bits {
+            8 => quote! { any::<i8>() },
+            16 => quote! { any::<i16>() },
+            32 => quote! { any::<i32>() },
+            64 => quote! { any::<i64>() },
+            128 => quote! { any::<i128>() },
+            _ => {
+                let shift = 64u16.saturating_sub(*bits) as u32;
+                quote! { any::<i64>().prop_map(|x| num_bigint::BigInt::from(x >> #shift)) }
+            }
+        },
+        AbiType::VarUint(_) | AbiType::VarInt(_) => {
+            quote! { any::<u32>().prop_map(num_bigint::BigUint::from) }
+        }
+        AbiType::Bool => quote! { any::<bool>() },
+        AbiType::Tuple(_) => {
+            let strategy_ident = strategy_ident(&type_name(rust_ty));
+            quote! { #strategy_ident() }
+        }
+        AbiType::Array(ty) => {
+            let strategy = quote_strategy(ty, generic_argument(rust_ty, 0));
+            quote! { proptest::collection::vec(#strategy, 0..4) }
+        }
+        AbiType::FixedArray(ty, size) => {
+            let strategy = quote_strategy(ty, generic_argument(rust_ty, 0));
+            let size = *size;
+            quote! { proptest::collection::vec(#strategy, #size) }
+        }
+        AbiType::Cell => quote! {
+            any::<u64>().prop_map(|x| everscale_types::prelude::CellBuilder::build_from(x).unwrap())
+        },
+        AbiType::Map(key, value) => {
+            let key = quote_strategy(&key.clone().into(), generic_argument(rust_ty, 0));
+            let value = quote_strategy(value, generic_argument(rust_ty, 1));
+            quote! { proptest::collection::hash_map(#key, #value, 0..4) }
+        }
+        AbiType::Address => quote! {
+            (prop_oneof![Just(0i8), Just(-1i8)], any::<[u8; 32]>()).prop_map(|(workchain, address)| {
+                everscale_types::models::message::StdAddr::new(
+                    workchain,
+                    everscale_types::prelude::HashBytes(address),
+                )
+            })
+        },
+        AbiType::Bytes => quote! { proptest::collection::vec(any::<u8>(), 0..64) },
+        AbiType::FixedBytes(size) => {
+            let size = *size;
+            quote! { proptest::collection::vec(any::<u8>(), #size) }
+        }
+        AbiType::String => quote! { "[a-zA-Z0-9 ]{0,32}" },
+        AbiType::Token => quote! {
+            any::<u64>().prop_map(|x| everscale_types::num::Tokens::new(x as u128))
+        },
+        AbiType::Optional(ty) => {
+            let strategy = quote_strategy(ty, generic_argument(rust_ty, 0));
+            quote! { proptest::option::of(#strategy) }
+        }
+        AbiType::Ref(ty) => quote_strategy(ty, rust_ty),
+    }
+}
+
+fn type_name(ty: &syn::Type) -> String {
+    let syn::Type::Path(path) = ty else {
+        panic!("Expected a struct type");
+    };
+    path.path.segments.last().unwrap().ident.to_string()
+}
+
+// type argument of `Vec<T>`, `Option<T>` or `HashMap<K, V>`
+fn generic_argument(ty: &syn::Type, index: usize) -> &syn::Type {
+    let syn::Type::Path(path) = ty else {
+        panic!("Expected a generic type");
+    };
+    let syn::PathArguments::AngleBracketed(args) = &path.path.segments.last().unwrap().arguments
+    else {
+        panic!("Expected a generic type");
+    };
+
+    args.args
+        .iter()
+        .filter_map(|arg| match arg {
+            syn::GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        })
+        .nth(index)
+        .expect("Missing generic argument")
+}
@@ -19,6 +19,7 @@ impl TraitImplGen {
         let with_abi_type_impls = self.implement_with_abi_type(name, properties);
         let into_abi_impls = self.implement_into_abi(name, properties);
         let from_abi_impls = self.implement_from_abi(name, properties);
+        let conversion_impls = self.implement_conversions(name);
 
         quote! {
             //WithAbiType implementations
@@ -29,6 +30,9 @@ impl TraitImplGen {
 
             //FromAbi implementations
             #from_abi_impls
+
+            //TryFrom/From implementations
+            #conversion_impls
         }
     }
 
@@ -55,23 +59,19 @@ impl TraitImplGen {
             [ #(#props_quote),* ]
         };
 
-        if !properties.is_empty() {
-            let properties_count = properties.len();
-            let tuple_tokens = quote! {
-                let properties: [NamedAbiType; #properties_count] = #props;
-            };
+        let properties_count = properties.len();
+        let tuple_tokens = quote! {
+            let properties: [NamedAbiType; #properties_count] = #props;
+        };
 
-            return quote! {
-                impl WithAbiType for #name_ident {
-                    fn abi_type() -> AbiType {
-                         #tuple_tokens
-                         AbiType::Tuple(std::sync::Arc::new(properties))
-                    }
+        quote! {
+            impl WithAbiType for #name_ident {
+                fn abi_type() -> AbiType {
+                     #tuple_tokens
+                     AbiType::Tuple(std::sync::Arc::new(properties))
                 }
-            };
+            }
         }
-
-        proc_macro2::TokenStream::new()
     }
 
     pub fn implement_from_abi(
@@ -94,23 +94,15 @@ impl TraitImplGen {
             #(#props)*
         };
 
-        if !props.is_empty() {
+        if props.is_empty() {
             return quote! {
                 impl FromAbi for #struct_name_ident {
                     fn from_abi(value: AbiValue) -> Result<Self> {
                         match value {
-                            AbiValue::Tuple(properties) =>  {
-                                let mut iterator = properties.into_iter();
-                                Ok(
-                                    #struct_name_ident {
-                                        #props_vec
-                                    }
-                                )
-
-                            },
+                            AbiValue::Tuple(properties) if properties.is_empty() => Ok(#struct_name_ident),
                             _ => Err(anyhow::Error::from(
                                 everscale_types::abi::error::AbiError::TypeMismatch {
-                                    expected: std::boxed::Box::<str>::from("tuple"),
+                                    expected: std::boxed::Box::<str>::from("()"),
                                     ty: value.display_type().to_string().into(),
                                 },
                             )),
@@ -120,7 +112,29 @@ impl TraitImplGen {
             };
         }
 
-        proc_macro2::TokenStream::new()
+        quote! {
+            impl FromAbi for #struct_name_ident {
+                fn from_abi(value: AbiValue) -> Result<Self> {
+                    match value {
+                        AbiValue::Tuple(properties) =>  {
+                            let mut iterator = properties.into_iter();
+                            Ok(
+                                #struct_name_ident {
+                                    #props_vec
+                                }
+                            )
+
+                        },
+                        _ => Err(anyhow::Error::from(
+                            everscale_types::abi::error::AbiError::TypeMismatch {
+                                expected: std::boxed::Box::<str>::from("tuple"),
+                                ty: value.display_type().to_string().into(),
+                            },
+                        )),
+                    }
+                }
+            }
+        }
     }
 
     pub fn implement_into_abi(
@@ -147,23 +161,50 @@ impl TraitImplGen {
             props.push(quote);
         }
 
-        if !props.is_empty() {
-            return quote! {
-                impl IntoAbi for #struct_name_ident {
-                    fn as_abi(&self) -> AbiValue {
-                        AbiValue::Tuple(vec![#(#props),*])
-                    }
+        quote! {
+            impl IntoAbi for #struct_name_ident {
+                fn as_abi(&self) -> AbiValue {
+                    AbiValue::Tuple(vec![#(#props),*])
+                }
 
-                    fn into_abi(self) -> AbiValue
-                    where
-                        Self: Sized,
-                    {
-                         AbiValue::Tuple(vec![#(#props),*])
-                    }
+                fn into_abi(self) -> AbiValue
+                where
+                    Self: Sized,
+                {
+                     AbiValue::Tuple(vec![#(#props),*])
                 }
-            };
+            }
         }
+    }
+
+    pub fn implement_conversions(&self, struct_name: &str) -> proc_macro2::TokenStream {
+        let struct_name_ident = format_ident!("{}", struct_name);
+
+        quote! {
+            impl TryFrom<Vec<NamedAbiValue>> for #struct_name_ident {
+                type Error = anyhow::Error;
 
-        proc_macro2::TokenStream::new()
+                fn try_from(values: Vec<NamedAbiValue>) -> Result<Self> {
+                    <#struct_name_ident as FromAbi>::from_abi(AbiValue::Tuple(values))
+                }
+            }
+
+            impl TryFrom<AbiValue> for #struct_name_ident {
+                type Error = anyhow::Error;
+
+                fn try_from(value: AbiValue) -> Result<Self> {
+                    <#struct_name_ident as FromAbi>::from_abi(value)
+                }
+            }
+
+            impl From<#struct_name_ident> for Vec<NamedAbiValue> {
+                fn from(value: #struct_name_ident) -> Self {
+                    match value.into_abi() {
+                        AbiValue::Tuple(values) => values,
+                        _ => unreachable!("generated structs are always encoded as tuples"),
+                    }
+                }
+            }
+        }
     }
 }
@@ -0,0 +1,113 @@
+use abi::abi;
+use abi_codegen::batch::BatchCall;
+use abi_codegen::contracts::qube;
+use abi_codegen::execution::ExecutionContext;
+use abi_codegen::existing_contract::{ExistingContract, FunctionDescr};
+use criterion::measurement::WallTime;
+use criterion::{criterion_group, criterion_main, BenchmarkGroup, BenchmarkId, Criterion};
+use everscale_types::boc::Boc;
+use everscale_types::models::{
+    Account, AccountState, CurrencyCollection, IntAddr, StateInit, StdAddr, StorageInfo,
+};
+use everscale_types::prelude::{CellBuilder, HashBytes};
+use nekoton_abi::LastTransactionId;
+
+// hand-assembled getter returning the `uint64` stored in its data
+#[abi("fixtures/getter.abi.json")]
+mod getter {}
+
+const GETTER_CODE: &[u8] = include_bytes!("../fixtures/getter.code.boc");
+
+// optional path to a saved Qube account, e.g. exported with `ExistingContract::save_to_file`
+const STATE_ENV: &str = "QUBE_STATE";
+
+const ACCOUNT_COUNTS: [usize; 3] = [10, 100, 1000];
+
+fn getter_contract(index: usize) -> ExistingContract {
+    let mut address = [0u8; 32];
+    address[..8].copy_from_slice(&(index as u64).to_be_bytes());
+
+    let account = Account {
+        address: IntAddr::Std(StdAddr::new(0, HashBytes(address))),
+        storage_stat: StorageInfo::default(),
+        last_trans_lt: 1,
+        balance: CurrencyCollection::new(1_000_000_000_000),
+        state: AccountState::Active(StateInit {
+            code: Some(Boc::decode(GETTER_CODE).unwrap()),
+            data: Some(CellBuilder::build_from(index as u64).unwrap()),
+            ..Default::default()
+        }),
+    };
+    ExistingContract::new(account, LastTransactionId::Inexact { latest_lt: 1 })
+}
+
+fn bench_contracts<T>(
+    group: &mut BenchmarkGroup<'_, WallTime>,
+    input: T::Input,
+    contracts: &[ExistingContract],
+    context: &ExecutionContext,
+) where
+    T: FunctionDescr,
+    T::Input: Clone,
+    T::Output: Send,
+{
+    let count = contracts.len();
+
+    group.bench_with_input(
+        BenchmarkId::new("loop", count),
+        contracts,
+        |b, contracts| {
+            b.iter(|| {
+                contracts
+                    .iter()
+                    .map(|contract| contract.run_local::<T>(input.clone(), context))
+                    .collect::<Vec<_>>()
+            })
+        },
+    );
+
+    group.bench_with_input(
+        BenchmarkId::new("batch", count),
+        contracts,
+        |b, contracts| {
+            let call = BatchCall::<T>::new(input.clone(), context).unwrap();
+            b.iter(|| call.run_all(contracts))
+        },
+    );
+}
+
+fn bench_batch(c: &mut Criterion) {
+    let context = ExecutionContext::default();
+
+    let mut group = c.benchmark_group("get_value");
+    for count in ACCOUNT_COUNTS {
+        let contracts: Vec<_> = (0..count).map(getter_contract).collect();
+        bench_contracts::<getter::functions::GetValue>(
+            &mut group,
+            getter::GetValueFunctionInput,
+            &contracts,
+            &context,
+        );
+    }
+    group.finish();
+
+    let Ok(path) = std::env::var(STATE_ENV) else {
+        return;
+    };
+    let contract = ExistingContract::from_file(path).expect("Failed to load Qube state");
+
+    let mut group = c.benchmark_group("calculate_gas_for_end_voting");
+    for count in ACCOUNT_COUNTS {
+        let contracts = vec![contract.clone(); count];
+        bench_contracts::<qube::functions::CalculateGasForEndVoting>(
+            &mut group,
+            qube::CalculateGasForEndVotingFunctionInput,
+            &contracts,
+            &context,
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_batch);
+criterion_main!(benches);
@@ -0,0 +1,120 @@
+use std::borrow::Borrow;
+use std::marker::PhantomData;
+
+use anyhow::Result;
+use everscale_types::models::message::StdAddr;
+use everscale_types::prelude::{Cell, HashBytes};
+use rayon::prelude::*;
+
+use crate::execution::ExecutionContext;
+use crate::existing_contract::{
+    external_body, input_tokens, into_output, is_responsible, ExistingContract, FunctionDescr,
+    RESPONSIBLE_VALUE,
+};
+use crate::tvm::VmParams;
+
+/// Getter call prepared once and run on many accounts.
+///
+/// The message body and the config are encoded up front and `now` is pinned, only
+/// the destination and the logical time differ between accounts. Contract codes are
+/// converted once per code hash and shared between accounts.
+pub struct BatchCall<T: FunctionDescr> {
+    body: Cell,
+    responsible: bool,
+    context: ExecutionContext,
+    params: VmParams,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: FunctionDescr> BatchCall<T> {
+    pub fn new(input: T::Input, context: &ExecutionContext) -> Result<Self> {
+        let function = T::function();
+        let tokens = input_tokens(input)?;
+        let context = context.pinned();
+        let mut params = context.vm_params(0)?;
+        params.code_cache = Some(Default::default());
+
+        let responsible = is_responsible(function);
+        let body = if responsible {
+            function.encode_internal_input(&tokens)?.build()?
+        } else {
+            external_body(function, &tokens, params.utime)?
+        };
+
+        Ok(Self {
+            body,
+            responsible,
+            context,
+            params,
+            _marker: PhantomData,
+        })
+    }
+
+    pub fn run(&self, contract: &ExistingContract) -> Result<T::Output> {
+        let params = VmParams {
+            lt: self.context.lt_after(contract.last_transaction().0),
+            ..self.params.clone()
+        };
+
+        let report = if self.responsible {
+            let sender = StdAddr::new(0, HashBytes::ZERO);
+            contract.run_internal_body::<T>(
+                self.body.clone(),
+                &sender,
+                RESPONSIBLE_VALUE,
+                &params,
+            )?
+        } else {
+            contract.run_external_body::<T>(self.body.clone(), &params)?
+        };
+
+        into_output::<T>(report)
+    }
+
+    /// Runs the call on every contract in parallel, results follow the order of `contracts`.
+    pub fn run_all<I>(&self, contracts: I) -> Vec<Result<T::Output>>
+    where
+        I: IntoIterator,
+        I::Item: Borrow<ExistingContract> + Send,
+        T::Output: Send,
+    {
+        let contracts: Vec<_> = contracts.into_iter().collect();
+        contracts
+            .into_par_iter()
+            .map(|contract| self.run(contract.borrow()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::existing_contract::tests::{getter, getter_contract};
+
+    type GetValue = getter::functions::GetValue;
+
+    #[test]
+    fn run_all_matches_run_local() {
+        let context = ExecutionContext::default().with_now(1_700_000_000);
+        let contracts: Vec<_> = (0..16).map(getter_contract).collect();
+
+        let call = BatchCall::<GetValue>::new(getter::GetValueFunctionInput, &context).unwrap();
+        let batch: Vec<_> = call
+            .run_all(&contracts)
+            .into_iter()
+            .map(|x| x.unwrap().value)
+            .collect();
+        let single: Vec<_> = contracts
+            .iter()
+            .map(|contract| {
+                contract
+                    .run_local::<GetValue>(getter::GetValueFunctionInput, &context)
+                    .unwrap()
+                    .value
+            })
+            .collect();
+
+        assert_eq!(batch, single);
+        assert_eq!(batch, (0..16).collect::<Vec<u64>>());
+    }
+}
@@ -1,3 +1,29 @@
 use abi::abi;
-#[abi("abi.json")]
+#[abi("abi.json", proptest, mutating("deployVoteEscrowAccount"))]
 pub mod qube {}
+
+#[cfg(test)]
+mod tests {
+    use everscale_types::abi::{Function, NamedAbiValue};
+    use everscale_types::models::message::StdAddr;
+    use everscale_types::prelude::HashBytes;
+
+    use super::qube;
+
+    #[test]
+    fn responsible_answers_are_decoded() {
+        let output = qube::GetVoteEscrowAccountAddressFunctionOutput {
+            value0: StdAddr::new(0, HashBytes([0x44; 32])),
+        };
+        let function = qube::functions::get_vote_escrow_account_address();
+        let tokens = Vec::<NamedAbiValue>::from(output.clone());
+        // answers start with the callback id chosen by the caller
+        let body = Function::encode_internal_msg_body(function.abi_version, 0x1234_5678, &tokens)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let decoded = qube::Output::decode_responsible(&body.as_slice().unwrap()).unwrap();
+        assert_eq!(decoded, qube::Output::GetVoteEscrowAccountAddress(output));
+    }
+}
@@ -0,0 +1,250 @@
+use std::collections::HashMap;
+use std::fmt::{Formatter, Result};
+
+use everscale_types::boc::Boc;
+use everscale_types::models::message::StdAddr;
+use everscale_types::num::Tokens;
+use everscale_types::prelude::{Cell, HashBytes};
+use num_bigint::{BigInt, BigUint};
+
+/// Human-readable formatting of ABI values, used by the generated `Display` implementations.
+pub trait AbiDisplay {
+    fn fmt_abi(&self, f: &mut Formatter<'_>) -> Result;
+
+    /// Renders a list of values, overridden by `u8` to print `bytes` as shortened hex.
+    fn fmt_abi_slice(items: &[Self], f: &mut Formatter<'_>) -> Result
+    where
+        Self: Sized,
+    {
+        f.write_str("[")?;
+        for (i, item) in items.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            item.fmt_abi(f)?;
+        }
+        f.write_str("]")
+    }
+}
+
+macro_rules! impl_abi_display_via_display {
+    ($($ty:ty),*) => {
+        $(impl AbiDisplay for $ty {
+            fn fmt_abi(&self, f: &mut Formatter<'_>) -> Result {
+                std::fmt::Display::fmt(self, f)
+            }
+        })*
+    };
+}
+
+impl_abi_display_via_display!(
+    u16, u32, u64, u128, i8, i16, i32, i64, i128, bool, BigUint, BigInt, Tokens
+);
+
+impl AbiDisplay for u8 {
+    fn fmt_abi(&self, f: &mut Formatter<'_>) -> Result {
+        std::fmt::Display::fmt(self, f)
+    }
+
+    fn fmt_abi_slice(items: &[Self], f: &mut Formatter<'_>) -> Result {
+        if items.is_empty() {
+            return f.write_str("[]");
+        }
+        fmt_shortened_hex(items, f)
+    }
+}
+
+impl AbiDisplay for String {
+    fn fmt_abi(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{self:?}")
+    }
+}
+
+impl AbiDisplay for HashBytes {
+    fn fmt_abi(&self, f: &mut Formatter<'_>) -> Result {
+        fmt_shortened_hex(&self.0, f)
+    }
+}
+
+impl AbiDisplay for [u8; 20] {
+    fn fmt_abi(&self, f: &mut Formatter<'_>) -> Result {
+        fmt_shortened_hex(self, f)
+    }
+}
+
+impl AbiDisplay for StdAddr {
+    fn fmt_abi(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{}:", self.workchain)?;
+        fmt_shortened_hex(&self.address.0, f)
+    }
+}
+
+impl AbiDisplay for Cell {
+    fn fmt_abi(&self, f: &mut Formatter<'_>) -> Result {
+        f.write_str("<cell ")?;
+        fmt_size(Boc::encode(self).len(), f)?;
+        f.write_str(" hash=")?;
+        fmt_shortened_hex(&self.repr_hash().0, f)?;
+        f.write_str(">")
+    }
+}
+
+impl<T: AbiDisplay> AbiDisplay for Vec<T> {
+    fn fmt_abi(&self, f: &mut Formatter<'_>) -> Result {
+        T::fmt_abi_slice(self, f)
+    }
+}
+
+// entries are sorted by key, so that the output doesn't depend on the hash order
+impl<K: AbiDisplay + Ord, V: AbiDisplay, S> AbiDisplay for HashMap<K, V, S> {
+    fn fmt_abi(&self, f: &mut Formatter<'_>) -> Result {
+        let mut entries: Vec<_> = self.iter().collect();
+        entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        f.write_str("{")?;
+        for (i, (key, value)) in entries.into_iter().enumerate() {
+            if i > 0 {
+                f.write_str(",")?;
+            }
+            f.write_str(" ")?;
+            key.fmt_abi(f)?;
+            f.write_str(": ")?;
+            value.fmt_abi(f)?;
+        }
+        if !self.is_empty() {
+            f.write_str(" ")?;
+        }
+        f.write_str("}")
+    }
+}
+
+impl<T: AbiDisplay> AbiDisplay for Option<T> {
+    fn fmt_abi(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Some(value) => value.fmt_abi(f),
+            None => f.write_str("none"),
+        }
+    }
+}
+
+// prints the first and the last two bytes, e.g. `ab12…cd34`
+fn fmt_shortened_hex(bytes: &[u8], f: &mut Formatter<'_>) -> Result {
+    if bytes.len() <= 4 {
+        return bytes.iter().try_for_each(|x| write!(f, "{x:02x}"));
+    }
+
+    let (head, tail) = (&bytes[..2], &bytes[bytes.len() - 2..]);
+    head.iter().try_for_each(|x| write!(f, "{x:02x}"))?;
+    f.write_str("…")?;
+    tail.iter().try_for_each(|x| write!(f, "{x:02x}"))
+}
+
+fn fmt_size(bytes: usize, f: &mut Formatter<'_>) -> Result {
+    const KIB: f64 = 1024.0;
+
+    match bytes as f64 {
+        size if size < KIB => write!(f, "{bytes} B"),
+        size if size < KIB * KIB => write!(f, "{:.1} KiB", size / KIB),
+        size => write!(f, "{:.1} MiB", size / KIB / KIB),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use everscale_types::prelude::CellBuilder;
+
+    use super::*;
+
+    struct Render<F>(F);
+
+    impl<F: Fn(&mut Formatter<'_>) -> Result> std::fmt::Display for Render<F> {
+        fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+            (self.0)(f)
+        }
+    }
+
+    fn shortened_hex(bytes: &[u8]) -> String {
+        Render(|f: &mut Formatter<'_>| fmt_shortened_hex(bytes, f)).to_string()
+    }
+
+    fn size(bytes: usize) -> String {
+        Render(|f: &mut Formatter<'_>| fmt_size(bytes, f)).to_string()
+    }
+
+    fn abi<T: AbiDisplay>(value: &T) -> String {
+        Render(|f: &mut Formatter<'_>| value.fmt_abi(f)).to_string()
+    }
+
+    #[test]
+    fn shortened_hex_keeps_short_values() {
+        assert_eq!(shortened_hex(&[]), "");
+        assert_eq!(shortened_hex(&[0x01, 0xab, 0x12, 0xff]), "01ab12ff");
+    }
+
+    #[test]
+    fn shortened_hex_keeps_first_and_last_two_bytes() {
+        assert_eq!(shortened_hex(&[0xab, 0x12, 0x00, 0xcd, 0x34]), "ab12…cd34");
+        assert_eq!(shortened_hex(&[0xab; 32]), "abab…abab");
+    }
+
+    #[test]
+    fn size_units() {
+        assert_eq!(size(0), "0 B");
+        assert_eq!(size(1023), "1023 B");
+        assert_eq!(size(1024), "1.0 KiB");
+        assert_eq!(size(1229), "1.2 KiB");
+        assert_eq!(size(3 * 1024 * 1024 / 2), "1.5 MiB");
+    }
+
+    #[test]
+    fn cell_shows_size_and_hash() {
+        let cell = CellBuilder::build_from(0xab12cd34u32).unwrap();
+        let hash = cell.repr_hash().0;
+
+        let expected = format!(
+            "<cell {} B hash={:02x}{:02x}…{:02x}{:02x}>",
+            Boc::encode(&cell).len(),
+            hash[0],
+            hash[1],
+            hash[30],
+            hash[31],
+        );
+        assert_eq!(abi(&cell), expected);
+    }
+
+    #[test]
+    fn kibibyte_cell() {
+        // nine full cells in a chain, about 1.2 KiB as a BOC
+        let mut cell = Cell::default();
+        for _ in 0..9 {
+            let mut builder = CellBuilder::new();
+            builder.store_raw(&[0xff; 128], 1023).unwrap();
+            builder.store_reference(cell).unwrap();
+            cell = builder.build().unwrap();
+        }
+
+        let rendered = abi(&cell);
+        assert!(rendered.starts_with("<cell 1.2 KiB hash="), "{rendered}");
+        assert!(rendered.ends_with('>'), "{rendered}");
+    }
+
+    #[test]
+    fn bytes_are_shortened_hex() {
+        assert_eq!(abi(&vec![0xabu8, 0x12, 0x00, 0xcd, 0x34]), "ab12…cd34");
+        assert_eq!(abi(&Vec::<u8>::new()), "[]");
+        assert_eq!(abi(&vec![vec![0x01u8, 0x02]]), "[0102]");
+        assert_eq!(abi(&vec![1u32, 2, 3]), "[1, 2, 3]");
+    }
+
+    #[test]
+    fn maps_are_sorted_by_key() {
+        let map: HashMap<u32, bool> = (0..32).rev().map(|x| (x, x % 2 == 0)).collect();
+        let expected = (0..32)
+            .map(|x| format!("{x}: {}", x % 2 == 0))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        assert_eq!(abi(&map), format!("{{ {expected} }}"));
+        assert_eq!(abi(&HashMap::<u32, bool>::new()), "{}");
+    }
+}
@@ -0,0 +1,322 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::Result;
+use everscale_types::abi::{Contract, NamedAbiValue};
+use everscale_types::boc::Boc;
+use everscale_types::models::config::{BlockchainConfig, ConfigParam21, ConfigParam8};
+use everscale_types::models::{IntAddr, Message, MsgInfo};
+use everscale_types::prelude::{Cell, CellSlice, HashBytes};
+
+use crate::existing_contract::ExistingContract;
+use crate::tvm::{self, VmParams};
+
+/// Block and config environment of local runs, unset values are filled with defaults.
+#[derive(Clone, Debug, Default)]
+pub struct ExecutionContext {
+    /// Config params dictionary, exposed to contracts through c7.
+    pub config_params: Option<Cell>,
+    /// Global capabilities, taken from config param 8 when loaded from a config.
+    pub capabilities: Option<u64>,
+    /// Gas price in nano per gas unit, taken from the basechain prices (config param 21)
+    /// when loaded from a config.
+    pub gas_price: Option<u64>,
+    /// Gas limit of a run, taken from the basechain prices (config param 21) when loaded
+    /// from a config.
+    pub gas_limit: Option<u64>,
+    /// Unix time, the system time by default.
+    pub now: Option<u32>,
+    /// Logical time, the one following the last account transaction by default.
+    pub lt: Option<u64>,
+    pub rand_seed: HashBytes,
+}
+
+impl ExecutionContext {
+    pub fn from_config(config: &BlockchainConfig) -> Result<Self> {
+        let capabilities = config
+            .params
+            .get::<ConfigParam8>()?
+            .map(|x| x.capabilities.into_inner());
+        let gas = config.params.get::<ConfigParam21>()?;
+
+        Ok(Self {
+            config_params: config.params.as_dict().root().clone(),
+            capabilities,
+            // stored in 1/65536 of nano
+            gas_price: gas.as_ref().map(|x| x.gas_price >> 16),
+            gas_limit: gas.map(|x| x.gas_limit),
+            ..Default::default()
+        })
+    }
+
+    pub fn from_config_boc(bytes: impl AsRef<[u8]>) -> Result<Self> {
+        Self::from_config(&Boc::decode(bytes)?.parse::<BlockchainConfig>()?)
+    }
+
+    pub fn from_config_file(path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_config_boc(std::fs::read(path)?)
+    }
+
+    pub fn with_now(mut self, now: u32) -> Self {
+        self.now = Some(now);
+        self
+    }
+
+    pub fn with_lt(mut self, lt: u64) -> Self {
+        self.lt = Some(lt);
+        self
+    }
+
+    pub fn with_rand_seed(mut self, rand_seed: HashBytes) -> Self {
+        self.rand_seed = rand_seed;
+        self
+    }
+
+    /// Same context with `now` fixed, so that runs at different moments share the time.
+    pub(crate) fn pinned(&self) -> Self {
+        let mut context = self.clone();
+        context.now.get_or_insert_with(now_sec);
+        context
+    }
+
+    pub(crate) fn vm_params(&self, last_trans_lt: u64) -> Result<VmParams> {
+        Ok(VmParams {
+            utime: self.now.unwrap_or_else(now_sec),
+            lt: self.lt_after(last_trans_lt),
+            rand_seed: self.rand_seed,
+            capabilities: self.capabilities.unwrap_or(tvm::DEFAULT_CAPABILITIES),
+            gas_limit: self.gas_limit.unwrap_or(tvm::DEFAULT_GAS_LIMIT),
+            gas_price: self.gas_price.unwrap_or(tvm::DEFAULT_GAS_PRICE),
+            config_params: self
+                .config_params
+                .as_ref()
+                .map(tvm::to_ton_cell)
+                .transpose()?,
+            code_cache: None,
+        })
+    }
+
+    /// Logical time of a run following the `last_trans_lt` transaction.
+    pub(crate) fn lt_after(&self, last_trans_lt: u64) -> u64 {
+        self.lt.unwrap_or(last_trans_lt + 1)
+    }
+}
+
+/// Everything observed during a local run.
+pub struct ExecutionReport<T> {
+    /// TVM exit code, `0` and `1` mean success.
+    pub exit_code: i32,
+    /// Action phase result code, `None` if the compute phase didn't commit, `0` means success.
+    pub action_result_code: Option<i32>,
+    pub gas_used: u64,
+    pub vm_steps: u32,
+    /// Function result, `None` if the run failed or the answer was not found.
+    pub output: Option<T>,
+    pub out_messages: Vec<OutMessage>,
+    /// Account state after the run.
+    pub account: ExistingContract,
+}
+
+impl<T> ExecutionReport<T> {
+    pub fn is_success(&self) -> bool {
+        self.is_compute_success() && matches!(self.action_result_code, None | Some(0))
+    }
+
+    pub fn is_compute_success(&self) -> bool {
+        self.exit_code == 0 || self.exit_code == 1
+    }
+
+    /// Function result, failing on unsuccessful runs.
+    pub fn into_output(self) -> Result<T> {
+        if !self.is_compute_success() {
+            anyhow::bail!("Execution failed with exit code {}", self.exit_code);
+        }
+        if let Some(code) = self.action_result_code.filter(|x| *x != 0) {
+            anyhow::bail!("Action phase failed with result code {code}");
+        }
+        self.output
+            .ok_or_else(|| anyhow::anyhow!("Function output not found"))
+    }
+
+    /// Events emitted during the run, in order.
+    pub fn events(&self) -> impl Iterator<Item = (&str, &[NamedAbiValue])> {
+        self.out_messages
+            .iter()
+            .filter_map(|message| match &message.decoded {
+                Some(DecodedMessage::Event { name, values }) => {
+                    Some((name.as_ref(), values.as_slice()))
+                }
+                _ => None,
+            })
+    }
+}
+
+fn now_sec() -> u32 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|x| x.as_secs() as u32)
+        .unwrap_or_default()
+}
+
+pub struct OutMessage {
+    pub message: Cell,
+    /// Message body decoded with the contract ABI, if it matched any function or event.
+    pub decoded: Option<DecodedMessage>,
+}
+
+impl OutMessage {
+    /// `address` is the account the message was sent from, internal messages are only
+    /// decoded as calls when sent back to it.
+    pub(crate) fn new(message: Cell, address: &IntAddr, contract: &Contract) -> Result<Self> {
+        let decoded = {
+            let parsed = message.parse::<Message<'_>>()?;
+            decode_message(&parsed.info, &parsed.body, address, contract)
+        };
+
+        Ok(Self { message, decoded })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum DecodedMessage {
+    /// External outbound message with an event.
+    Event {
+        name: Arc<str>,
+        values: Vec<NamedAbiValue>,
+    },
+    /// External outbound message with a function result.
+    Output {
+        name: Arc<str>,
+        values: Vec<NamedAbiValue>,
+    },
+    /// Internal message calling a function of the same contract.
+    Call {
+        name: Arc<str>,
+        values: Vec<NamedAbiValue>,
+    },
+}
+
+// unknown ids, malformed bodies and internal messages to other accounts are left undecoded
+fn decode_message(
+    info: &MsgInfo,
+    body: &CellSlice<'_>,
+    address: &IntAddr,
+    contract: &Contract,
+) -> Option<DecodedMessage> {
+    let id = body.get_u32(0).ok()?;
+
+    match info {
+        MsgInfo::ExtOut(_) => {
+            if let Some(event) = contract.events.values().find(|x| x.id == id) {
+                let values = event.decode_internal_input(body.clone()).ok()?;
+                return Some(DecodedMessage::Event {
+                    name: event.name.clone(),
+                    values,
+                });
+            }
+
+            let function = contract.functions.values().find(|x| x.output_id == id)?;
+            let values = function.decode_output(body.clone()).ok()?;
+            Some(DecodedMessage::Output {
+                name: function.name.clone(),
+                values,
+            })
+        }
+        MsgInfo::Int(info) => {
+            if info.dst != *address {
+                return None;
+            }
+            let function = contract.functions.values().find(|x| x.input_id == id)?;
+            let values = function.decode_internal_input(body.clone()).ok()?;
+            Some(DecodedMessage::Call {
+                name: function.name.clone(),
+                values,
+            })
+        }
+        MsgInfo::ExtIn(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use everscale_types::cell::CellSliceRange;
+    use everscale_types::models::{CurrencyCollection, IntMsgInfo, OwnedMessage, StdAddr};
+    use everscale_types::prelude::CellBuilder;
+
+    use super::*;
+    use crate::contracts::qube;
+    use crate::existing_contract::FunctionDescr;
+
+    type CalculateGas = qube::functions::CalculateGasForEndVoting;
+
+    #[test]
+    fn defaults_fill_vm_params() {
+        let params = ExecutionContext::default().vm_params(41).unwrap();
+        assert_eq!(params.lt, 42);
+        assert_eq!(params.gas_limit, tvm::DEFAULT_GAS_LIMIT);
+        assert_eq!(params.gas_price, tvm::DEFAULT_GAS_PRICE);
+        assert_eq!(params.capabilities, tvm::DEFAULT_CAPABILITIES);
+        assert!(params.config_params.is_none());
+    }
+
+    #[test]
+    fn overrides_reach_vm_params() {
+        let context = ExecutionContext {
+            gas_price: Some(10),
+            gas_limit: Some(500),
+            ..Default::default()
+        }
+        .with_now(1_700_000_000)
+        .with_lt(77)
+        .with_rand_seed(HashBytes([0x55; 32]));
+
+        let params = context.vm_params(41).unwrap();
+        assert_eq!(params.utime, 1_700_000_000);
+        assert_eq!(params.lt, 77);
+        assert_eq!(params.rand_seed, HashBytes([0x55; 32]));
+        assert_eq!(params.gas_price, 10);
+        assert_eq!(params.gas_limit, 500);
+    }
+
+    #[test]
+    fn pinned_keeps_explicit_now() {
+        let context = ExecutionContext::default().with_now(1_700_000_000);
+        assert_eq!(context.pinned().now, Some(1_700_000_000));
+        assert!(ExecutionContext::default().pinned().now.is_some());
+    }
+
+    fn internal_call(dst: &IntAddr) -> Cell {
+        let body = CalculateGas::function()
+            .encode_internal_input(&[])
+            .unwrap()
+            .build()
+            .unwrap();
+        let range = CellSliceRange::full(body.as_ref());
+        let message = OwnedMessage {
+            info: MsgInfo::Int(IntMsgInfo {
+                dst: dst.clone(),
+                value: CurrencyCollection::new(1_000_000_000),
+                ..Default::default()
+            }),
+            init: None,
+            body: (body, range),
+            layout: None,
+        };
+        CellBuilder::build_from(&message).unwrap()
+    }
+
+    #[test]
+    fn calls_are_decoded_only_for_the_same_account() {
+        let address = IntAddr::Std(StdAddr::new(0, HashBytes([0x11; 32])));
+        let other = IntAddr::Std(StdAddr::new(0, HashBytes([0x22; 32])));
+
+        let own = OutMessage::new(internal_call(&address), &address, CalculateGas::contract());
+        assert!(matches!(
+            own.unwrap().decoded,
+            Some(DecodedMessage::Call { name, .. }) if name.as_ref() == "calculateGasForEndVoting"
+        ));
+
+        let foreign = OutMessage::new(internal_call(&other), &address, CalculateGas::contract());
+        assert!(foreign.unwrap().decoded.is_none());
+    }
+}
@@ -1,11 +1,39 @@
+use std::path::Path;
+
 use anyhow::Result;
-use everscale_types::abi::{AbiValue, Event, FromAbi, Function, IntoAbi, WithAbiType};
-use everscale_types::models::Account;
-use nekoton_abi::LastTransactionId;
+use everscale_types::abi::{
+    AbiValue, Contract, Event, FromAbi, Function, IntoAbi, NamedAbiValue, WithAbiType,
+};
+use everscale_types::boc::Boc;
+use everscale_types::cell::CellSliceRange;
+use everscale_types::models::message::{IntAddr, StdAddr};
+use everscale_types::models::{
+    Account, AccountState, AccountStatus, CurrencyCollection, ExtInMsgInfo, IntMsgInfo, Message,
+    MsgInfo, OptionalAccount, OwnedMessage, ShardAccount, StateInit,
+};
+use everscale_types::num::Tokens;
+use everscale_types::prelude::{Cell, CellBuilder, HashBytes, Lazy};
+use nekoton_abi::{LastTransactionId, TransactionId};
+use serde::{Deserialize, Serialize};
+
+use crate::execution::{DecodedMessage, ExecutionContext, ExecutionReport, OutMessage};
+use crate::tvm::{self, VmParams};
+
+// last transaction hash and lt, the account itself is stored in a reference
+const SHARD_ACCOUNT_BITS: u16 = 256 + 64;
+
+const EXTERNAL_MESSAGE_TTL: u32 = 60;
 
+const ANSWER_ID_INPUT: &str = "answerId";
+
+// attached to responsible calls so that the answer can be paid for
+pub(crate) const RESPONSIBLE_VALUE: Tokens = Tokens::new(100_000_000_000);
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct ExistingContract {
-    //#[serde(with = "serde_account_stuff")]
+    #[serde(with = "serde_account")]
     pub account: everscale_types::models::Account,
+    #[serde(with = "serde_last_transaction_id")]
     pub last_transaction_id: LastTransactionId,
 }
 
@@ -17,22 +45,420 @@ impl ExistingContract {
         }
     }
 
-    pub fn run_local<T: FunctionDescr>(&self, input: T::Input) -> Result<T::Output> {
-        let _function = T::function();
+    pub fn from_boc(bytes: impl AsRef<[u8]>) -> Result<Self> {
+        Self::from_cell(Boc::decode(bytes)?)
+    }
+
+    pub fn from_boc_base64(data: &str) -> Result<Self> {
+        Self::from_cell(Boc::decode_base64(data.trim())?)
+    }
+
+    pub fn from_boc_hex(data: &str) -> Result<Self> {
+        Self::from_boc(hex::decode(data.trim())?)
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_boc(std::fs::read(path)?)
+    }
+
+    /// Loads either a `ShardAccount` or a bare `Account`.
+    ///
+    /// A bare account has no last transaction hash, so its id is inexact.
+    pub fn from_cell(cell: Cell) -> Result<Self> {
+        if cell.bit_len() == SHARD_ACCOUNT_BITS && cell.reference_count() == 1 {
+            let shard_account = cell.parse::<ShardAccount>()?;
+            let Some(account) = shard_account.account.load()?.0 else {
+                anyhow::bail!("Account does not exist");
+            };
 
-        let AbiValue::Tuple(_) = input.into_abi() else {
-            anyhow::bail!("Expected input as tuple");
+            let last_transaction_id = if shard_account.last_trans_hash == HashBytes::ZERO {
+                LastTransactionId::Inexact {
+                    latest_lt: shard_account.last_trans_lt,
+                }
+            } else {
+                LastTransactionId::Exact(TransactionId {
+                    lt: shard_account.last_trans_lt,
+                    hash: ton_types::UInt256::from(shard_account.last_trans_hash.0),
+                })
+            };
+
+            return Ok(Self::new(account, last_transaction_id));
+        }
+
+        let Some(account) = cell.parse::<OptionalAccount>()?.0 else {
+            anyhow::bail!("Account does not exist");
+        };
+        let last_transaction_id = LastTransactionId::Inexact {
+            latest_lt: account.last_trans_lt,
         };
 
-        // TODO: call here smth
-        Err(anyhow::Error::msg("not implemented"))
+        Ok(Self::new(account, last_transaction_id))
+    }
+
+    pub(crate) fn last_transaction(&self) -> (u64, Option<HashBytes>) {
+        match &self.last_transaction_id {
+            LastTransactionId::Exact(id) => (id.lt, Some(HashBytes(id.hash.inner()))),
+            LastTransactionId::Inexact { latest_lt } => (*latest_lt, None),
+        }
+    }
+
+    pub fn address(&self) -> Result<&StdAddr> {
+        match &self.account.address {
+            IntAddr::Std(address) => Ok(address),
+            IntAddr::Var(_) => anyhow::bail!("Unsupported account address"),
+        }
+    }
+
+    pub fn balance(&self) -> Tokens {
+        self.account.balance.tokens
+    }
+
+    pub fn status(&self) -> AccountStatus {
+        self.account.state.status()
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.status() == AccountStatus::Active
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        self.status() == AccountStatus::Frozen
+    }
+
+    pub fn is_uninit(&self) -> bool {
+        self.status() == AccountStatus::Uninit
+    }
+
+    fn state_init(&self) -> Option<&StateInit> {
+        match &self.account.state {
+            AccountState::Active(state_init) => Some(state_init),
+            AccountState::Frozen(_) | AccountState::Uninit => None,
+        }
+    }
+
+    /// Code of an active account.
+    pub fn code(&self) -> Option<&Cell> {
+        self.state_init()?.code.as_ref()
+    }
+
+    /// Data of an active account.
+    pub fn data(&self) -> Option<&Cell> {
+        self.state_init()?.data.as_ref()
+    }
+
+    pub fn code_hash(&self) -> Option<&HashBytes> {
+        self.code().map(|code| code.repr_hash())
+    }
+
+    /// Unix time of the last storage fee payment.
+    pub fn last_paid(&self) -> u32 {
+        self.account.storage_stat.last_paid
+    }
+
+    /// Serializes the contract as a `ShardAccount`, the inverse of `from_cell`.
+    pub fn to_cell(&self) -> Result<Cell> {
+        let (last_trans_lt, last_trans_hash) = self.last_transaction();
+
+        let shard_account = ShardAccount {
+            account: Lazy::new(&OptionalAccount(Some(self.account.clone())))?,
+            last_trans_hash: last_trans_hash.unwrap_or(HashBytes::ZERO),
+            last_trans_lt,
+        };
+
+        Ok(CellBuilder::build_from(&shard_account)?)
+    }
+
+    pub fn to_boc(&self) -> Result<Vec<u8>> {
+        Ok(Boc::encode(self.to_cell()?))
+    }
+
+    pub fn to_boc_base64(&self) -> Result<String> {
+        Ok(Boc::encode_base64(self.to_cell()?))
+    }
+
+    pub fn to_boc_hex(&self) -> Result<String> {
+        Ok(hex::encode(self.to_boc()?))
+    }
+
+    pub fn save_to_file(&self, path: impl AsRef<Path>) -> Result<()> {
+        std::fs::write(path, self.to_boc()?)?;
+        Ok(())
+    }
+
+    pub fn run_local<T: FunctionDescr>(
+        &self,
+        input: T::Input,
+        context: &ExecutionContext,
+    ) -> Result<T::Output> {
+        into_output::<T>(self.run_local_report::<T>(input, context)?)
+    }
+
+    pub fn run_local_responsible<T: FunctionDescr>(
+        &self,
+        input: T::Input,
+        context: &ExecutionContext,
+    ) -> Result<T::Output> {
+        into_output::<T>(self.run_local_responsible_report::<T>(input, context)?)
+    }
+
+    /// Runs a getter through an unsigned external message, the output is read from
+    /// the external outbound message with the function result.
+    pub fn run_local_report<T: FunctionDescr>(
+        &self,
+        input: T::Input,
+        context: &ExecutionContext,
+    ) -> Result<ExecutionReport<T::Output>> {
+        let params = context.vm_params(self.last_transaction().0)?;
+        let body = external_body(T::function(), &input_tokens(input)?, params.utime)?;
+        self.run_external_body::<T>(body, &params)
+    }
+
+    /// Runs a responsible getter through an internal message, the output is read from
+    /// the answer sent back to the sender.
+    pub fn run_local_responsible_report<T: FunctionDescr>(
+        &self,
+        input: T::Input,
+        context: &ExecutionContext,
+    ) -> Result<ExecutionReport<T::Output>> {
+        let sender = StdAddr::new(0, HashBytes::ZERO);
+        self.simulate_internal::<T>(input, &sender, RESPONSIBLE_VALUE, context)
+    }
+
+    /// Simulates an internal call from `sender` on a copy of the account, `value` is credited
+    /// to the account before execution.
+    ///
+    /// The output is only set for responsible functions, from the answer sent back to the sender.
+    pub fn simulate_internal<T: FunctionDescr>(
+        &self,
+        input: T::Input,
+        sender: &StdAddr,
+        value: Tokens,
+        context: &ExecutionContext,
+    ) -> Result<ExecutionReport<T::Output>> {
+        let params = context.vm_params(self.last_transaction().0)?;
+        let body = T::function()
+            .encode_internal_input(&input_tokens(input)?)?
+            .build()?;
+        self.run_internal_body::<T>(body, sender, value, &params)
+    }
+
+    pub(crate) fn run_external_body<T: FunctionDescr>(
+        &self,
+        body: Cell,
+        params: &VmParams,
+    ) -> Result<ExecutionReport<T::Output>> {
+        self.check_code_hash::<T>()?;
+
+        let function = T::function();
+        let message = external_message(self.address()?, body)?;
+
+        let mut report = self.execute(&self.account, &message, params, T::contract())?;
+        if report.is_success() {
+            let output = report.out_messages.iter().find_map(|x| match &x.decoded {
+                Some(DecodedMessage::Output { name, values }) if *name == function.name => {
+                    Some(values.clone())
+                }
+                _ => None,
+            });
+            report.output = output
+                .map(|values| T::Output::from_abi(AbiValue::Tuple(values)))
+                .transpose()?;
+        }
+
+        Ok(report)
+    }
+
+    pub(crate) fn run_internal_body<T: FunctionDescr>(
+        &self,
+        body: Cell,
+        sender: &StdAddr,
+        value: Tokens,
+        params: &VmParams,
+    ) -> Result<ExecutionReport<T::Output>> {
+        self.check_code_hash::<T>()?;
+
+        let function = T::function();
+        //the callback id follows the function id
+        let answer_id = if is_responsible(function) {
+            Some(body.as_slice()?.get_u32(32)?)
+        } else {
+            None
+        };
+        let message = internal_message(sender, self.address()?, value, body, params)?;
+
+        let mut account = self.account.clone();
+        account.balance.tokens = account
+            .balance
+            .tokens
+            .checked_add(value)
+            .ok_or_else(|| anyhow::anyhow!("Balance overflow"))?;
+
+        let mut report = self.execute(&account, &message, params, T::contract())?;
+        if let Some(answer_id) = answer_id.filter(|_| report.is_success()) {
+            let mut output = None;
+            for message in &report.out_messages {
+                if let Some(values) = decode_answer(&message.message, sender, answer_id, function)?
+                {
+                    output = Some(T::Output::from_abi(AbiValue::Tuple(values))?);
+                    break;
+                }
+            }
+            report.output = output;
+        }
+
+        Ok(report)
+    }
+
+    fn check_code_hash<T: FunctionDescr>(&self) -> Result<()> {
+        let accepted = T::code_hashes();
+        if accepted.is_empty() {
+            return Ok(());
+        }
+
+        let code_hash = self.code_hash().copied();
+        match &code_hash {
+            Some(code_hash) if accepted.contains(code_hash) => Ok(()),
+            _ => Err(UnexpectedCodeHash { code_hash }.into()),
+        }
+    }
+
+    fn execute<O>(
+        &self,
+        account: &Account,
+        message: &Cell,
+        params: &VmParams,
+        contract: &Contract,
+    ) -> Result<ExecutionReport<O>> {
+        let output = tvm::execute_message(account, message, params)?;
+
+        let out_messages = output
+            .out_messages
+            .into_iter()
+            .map(|message| OutMessage::new(message, &account.address, contract))
+            .collect::<Result<Vec<_>>>()?;
+
+        // unaccepted externals leave the account and its last transaction as is
+        let last_transaction_id = if output.accepted {
+            LastTransactionId::Inexact {
+                latest_lt: params.lt,
+            }
+        } else {
+            self.last_transaction_id.clone()
+        };
+
+        Ok(ExecutionReport {
+            exit_code: output.exit_code,
+            action_result_code: output.action_result_code,
+            gas_used: output.gas_used,
+            vm_steps: output.vm_steps,
+            output: None,
+            out_messages,
+            account: ExistingContract::new(output.account, last_transaction_id),
+        })
+    }
+}
+
+// known contract errors take precedence over the bare exit code
+pub(crate) fn into_output<T: FunctionDescr>(
+    report: ExecutionReport<T::Output>,
+) -> Result<T::Output> {
+    if !report.is_compute_success() {
+        if let Some(error) = T::error(report.exit_code) {
+            return Err(error);
+        }
+    }
+    report.into_output()
+}
+
+pub(crate) fn input_tokens<T: IntoAbi>(input: T) -> Result<Vec<NamedAbiValue>> {
+    let AbiValue::Tuple(tokens) = input.into_abi() else {
+        anyhow::bail!("Expected input as tuple");
+    };
+    Ok(tokens)
+}
+
+// unsigned body with the time and expiration headers set from the run time
+pub(crate) fn external_body(
+    function: &Function,
+    tokens: &[NamedAbiValue],
+    utime: u32,
+) -> Result<Cell> {
+    function
+        .encode_external(tokens)
+        .set_time(u64::from(utime) * 1000)
+        .set_expire_at(utime + EXTERNAL_MESSAGE_TTL)
+        .build_input()?
+        .without_signature()
+}
+
+fn external_message(dst: &StdAddr, body: Cell) -> Result<Cell> {
+    let range = CellSliceRange::full(body.as_ref());
+    let message = OwnedMessage {
+        info: MsgInfo::ExtIn(ExtInMsgInfo {
+            src: None,
+            dst: IntAddr::Std(dst.clone()),
+            import_fee: Tokens::ZERO,
+        }),
+        init: None,
+        body: (body, range),
+        layout: None,
+    };
+
+    Ok(CellBuilder::build_from(&message)?)
+}
+
+fn internal_message(
+    src: &StdAddr,
+    dst: &StdAddr,
+    value: Tokens,
+    body: Cell,
+    params: &VmParams,
+) -> Result<Cell> {
+    let range = CellSliceRange::full(body.as_ref());
+    let message = OwnedMessage {
+        info: MsgInfo::Int(IntMsgInfo {
+            ihr_disabled: true,
+            bounce: true,
+            src: IntAddr::Std(src.clone()),
+            dst: IntAddr::Std(dst.clone()),
+            value: CurrencyCollection::new(value.into_inner()),
+            created_lt: params.lt,
+            created_at: params.utime,
+            ..Default::default()
+        }),
+        init: None,
+        body: (body, range),
+        layout: None,
+    };
+
+    Ok(CellBuilder::build_from(&message)?)
+}
+
+pub(crate) fn is_responsible(function: &Function) -> bool {
+    matches!(function.inputs.first(), Some(input) if input.name.as_ref() == ANSWER_ID_INPUT)
+}
+
+// responsible answer: an internal message back to the sender with the callback id
+// followed by the function result, other messages to the sender (e.g. refunds) are skipped
+fn decode_answer(
+    message: &Cell,
+    sender: &StdAddr,
+    answer_id: u32,
+    function: &Function,
+) -> Result<Option<Vec<NamedAbiValue>>> {
+    let message = message.parse::<Message<'_>>()?;
+    let MsgInfo::Int(info) = &message.info else {
+        return Ok(None);
+    };
+    if !matches!(&info.dst, IntAddr::Std(dst) if dst == sender) {
+        return Ok(None);
     }
 
-    pub fn run_local_responsible<T: FunctionDescr>(&self, input: T::Input) -> Result<T::Output> {
-        let _ = input.into_abi();
-        // TODO: call here smth
-        Err(anyhow::Error::msg("not implemented"))
+    let mut slice = message.body.clone();
+    if slice.load_u32().ok() != Some(answer_id) {
+        return Ok(None);
     }
+    Ok(NamedAbiValue::load_tuple(&function.outputs, function.abi_version, &mut slice).ok())
 }
 
 pub trait FunctionDescr {
@@ -40,10 +466,430 @@ pub trait FunctionDescr {
     type Output: WithAbiType + IntoAbi + FromAbi;
 
     fn function() -> &'static Function;
+
+    fn contract() -> &'static Contract;
+
+    /// Typed contract error for an exit code, see the `errors` parameter of `#[abi]`.
+    fn error(_exit_code: i32) -> Option<anyhow::Error> {
+        None
+    }
+
+    /// Accepted code hashes, any code is accepted when empty, see the `code_hashes` parameter
+    /// of `#[abi]`.
+    fn code_hashes() -> &'static [HashBytes] {
+        &[]
+    }
+}
+
+/// The account code is not one of the versions the function descriptor was generated for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnexpectedCodeHash {
+    /// `None` if the account has no code.
+    pub code_hash: Option<HashBytes>,
 }
 
+impl std::fmt::Display for UnexpectedCodeHash {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.code_hash {
+            Some(code_hash) => write!(f, "Unexpected contract code hash: {code_hash}"),
+            None => f.write_str("Contract has no code"),
+        }
+    }
+}
+
+impl std::error::Error for UnexpectedCodeHash {}
+
 pub trait EventDescr {
     type Input: WithAbiType + IntoAbi + FromAbi;
 
     fn event() -> &'static Event;
 }
+
+// account as a base64 encoded BOC
+mod serde_account {
+    use everscale_types::boc::Boc;
+    use everscale_types::models::Account;
+    use everscale_types::prelude::CellBuilder;
+    use serde::de::Error as _;
+    use serde::ser::Error as _;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(account: &Account, serializer: S) -> Result<S::Ok, S::Error> {
+        let cell = CellBuilder::build_from(account).map_err(S::Error::custom)?;
+        serializer.serialize_str(&Boc::encode_base64(cell))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Account, D::Error> {
+        let data = String::deserialize(deserializer)?;
+        let cell = Boc::decode_base64(data).map_err(D::Error::custom)?;
+        cell.parse::<Account>().map_err(D::Error::custom)
+    }
+}
+
+// last transaction id as its lt and hex encoded hash, the hash is omitted for inexact ids
+mod serde_last_transaction_id {
+    use nekoton_abi::{LastTransactionId, TransactionId};
+    use serde::de::Error as _;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    struct SerdeLastTransactionId {
+        lt: u64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        hash: Option<String>,
+    }
+
+    pub fn serialize<S: Serializer>(
+        id: &LastTransactionId,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let id = match id {
+            LastTransactionId::Exact(id) => SerdeLastTransactionId {
+                lt: id.lt,
+                hash: Some(hex::encode(id.hash.inner())),
+            },
+            LastTransactionId::Inexact { latest_lt } => SerdeLastTransactionId {
+                lt: *latest_lt,
+                hash: None,
+            },
+        };
+        id.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<LastTransactionId, D::Error> {
+        let SerdeLastTransactionId { lt, hash } =
+            SerdeLastTransactionId::deserialize(deserializer)?;
+
+        let Some(hash) = hash else {
+            return Ok(LastTransactionId::Inexact { latest_lt: lt });
+        };
+
+        let hash: [u8; 32] = hex::decode(hash)
+            .map_err(D::Error::custom)?
+            .try_into()
+            .map_err(|_| D::Error::custom("Invalid transaction hash length"))?;
+
+        Ok(LastTransactionId::Exact(TransactionId {
+            lt,
+            hash: ton_types::UInt256::from(hash),
+        }))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use abi::abi;
+    use everscale_types::models::StorageInfo;
+
+    use super::*;
+    use crate::contracts::qube;
+
+    // pinned to a hash no test account has
+    #[abi(
+        "abi.json",
+        code_hashes("5b1a5f8e6e2d4f0c9a7b3c1d2e4f60718293a4b5c6d7e8f90a1b2c3d4e5f6071")
+    )]
+    mod pinned_qube {}
+
+    // returns the `uint64` stored in its data and emits `ValueRead`, the code is assembled
+    // by hand: BLKDROP 5, ACCEPT, send the event from the first reference, then load c4
+    // and send it after the output prefix from the second reference
+    #[abi("fixtures/getter.abi.json", errors = "fixtures/getter_errors.json")]
+    pub mod getter {}
+
+    const GETTER_CODE: &[u8] = include_bytes!("../fixtures/getter.code.boc");
+
+    pub(crate) fn getter_contract(value: u64) -> ExistingContract {
+        let mut account = test_account();
+        account.state = AccountState::Active(StateInit {
+            code: Some(Boc::decode(GETTER_CODE).unwrap()),
+            data: Some(CellBuilder::build_from(value).unwrap()),
+            ..Default::default()
+        });
+        ExistingContract::new(account, LastTransactionId::Inexact { latest_lt: 42 })
+    }
+
+    fn test_account() -> Account {
+        Account {
+            address: IntAddr::Std(StdAddr::new(0, HashBytes([0x11; 32]))),
+            storage_stat: StorageInfo {
+                last_paid: 1_700_000_000,
+                ..Default::default()
+            },
+            last_trans_lt: 42,
+            balance: CurrencyCollection::new(1_000_000_000),
+            state: AccountState::Active(StateInit {
+                code: Some(CellBuilder::build_from(0xdeadbeefu32).unwrap()),
+                data: Some(CellBuilder::build_from(0x0123u16).unwrap()),
+                ..Default::default()
+            }),
+        }
+    }
+
+    pub(crate) fn exact_contract() -> ExistingContract {
+        ExistingContract::new(
+            test_account(),
+            LastTransactionId::Exact(TransactionId {
+                lt: 42,
+                hash: ton_types::UInt256::from([0x22; 32]),
+            }),
+        )
+    }
+
+    pub(crate) fn assert_same(a: &ExistingContract, b: &ExistingContract) {
+        assert_eq!(
+            CellBuilder::build_from(&a.account).unwrap().repr_hash(),
+            CellBuilder::build_from(&b.account).unwrap().repr_hash(),
+        );
+        assert_eq!(a.last_transaction(), b.last_transaction());
+    }
+
+    #[test]
+    fn round_trip_formats() {
+        let contract = exact_contract();
+
+        let from_boc = ExistingContract::from_boc(contract.to_boc().unwrap()).unwrap();
+        assert_same(&contract, &from_boc);
+
+        let from_base64 =
+            ExistingContract::from_boc_base64(&contract.to_boc_base64().unwrap()).unwrap();
+        assert_same(&contract, &from_base64);
+
+        let from_hex = ExistingContract::from_boc_hex(&contract.to_boc_hex().unwrap()).unwrap();
+        assert_same(&contract, &from_hex);
+
+        let path = std::env::temp_dir().join(format!(
+            "existing_contract_round_trip_{}.boc",
+            std::process::id()
+        ));
+        contract.save_to_file(&path).unwrap();
+        let from_file = ExistingContract::from_file(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_same(&contract, &from_file.unwrap());
+    }
+
+    #[test]
+    fn zero_hash_is_inexact() {
+        let contract =
+            ExistingContract::new(test_account(), LastTransactionId::Inexact { latest_lt: 42 });
+
+        let loaded = ExistingContract::from_boc(contract.to_boc().unwrap()).unwrap();
+        assert_same(&contract, &loaded);
+        assert!(matches!(
+            loaded.last_transaction_id,
+            LastTransactionId::Inexact { latest_lt: 42 }
+        ));
+    }
+
+    #[test]
+    fn bare_account_is_inexact() {
+        let account = test_account();
+        let cell = CellBuilder::build_from(&OptionalAccount(Some(account.clone()))).unwrap();
+
+        let loaded = ExistingContract::from_cell(cell).unwrap();
+        assert_same(
+            &ExistingContract::new(account, LastTransactionId::Inexact { latest_lt: 42 }),
+            &loaded,
+        );
+    }
+
+    #[test]
+    fn serde_exact_round_trip() {
+        let contract = exact_contract();
+
+        let json = serde_json::to_value(&contract).unwrap();
+        assert_eq!(json["last_transaction_id"]["lt"], 42);
+        assert_eq!(json["last_transaction_id"]["hash"], hex::encode([0x22; 32]));
+
+        let loaded: ExistingContract = serde_json::from_value(json).unwrap();
+        assert_same(&contract, &loaded);
+    }
+
+    #[test]
+    fn serde_inexact_round_trip() {
+        let contract =
+            ExistingContract::new(test_account(), LastTransactionId::Inexact { latest_lt: 42 });
+
+        let json = serde_json::to_value(&contract).unwrap();
+        assert!(json["last_transaction_id"].get("hash").is_none());
+
+        let loaded: ExistingContract = serde_json::from_value(json).unwrap();
+        assert_same(&contract, &loaded);
+        assert!(matches!(
+            loaded.last_transaction_id,
+            LastTransactionId::Inexact { latest_lt: 42 }
+        ));
+    }
+
+    #[test]
+    fn serde_rejects_bad_hash_length() {
+        let mut json = serde_json::to_value(exact_contract()).unwrap();
+        json["last_transaction_id"]["hash"] = serde_json::Value::from(hex::encode([0x22; 31]));
+
+        let error = serde_json::from_value::<ExistingContract>(json)
+            .err()
+            .expect("Short hash must be rejected");
+        assert!(error
+            .to_string()
+            .contains("Invalid transaction hash length"));
+    }
+
+    #[test]
+    fn unpinned_functions_accept_any_code() {
+        exact_contract()
+            .check_code_hash::<qube::functions::CalculateGasForEndVoting>()
+            .unwrap();
+    }
+
+    #[test]
+    fn mismatched_code_hash_is_rejected() {
+        let contract = exact_contract();
+        let err = contract
+            .run_local::<pinned_qube::functions::CalculateGasForEndVoting>(
+                pinned_qube::CalculateGasForEndVotingFunctionInput,
+                &ExecutionContext::default(),
+            )
+            .err()
+            .expect("Pinned function must reject unknown code");
+
+        assert_eq!(
+            err.downcast_ref::<UnexpectedCodeHash>(),
+            Some(&UnexpectedCodeHash {
+                code_hash: contract.code_hash().copied(),
+            })
+        );
+    }
+
+    #[test]
+    fn getter_runs_on_the_vm() {
+        let contract = getter_contract(42);
+        let report = contract
+            .run_local_report::<getter::functions::GetValue>(
+                getter::GetValueFunctionInput,
+                &ExecutionContext::default(),
+            )
+            .unwrap();
+
+        assert_eq!(report.exit_code, 0);
+        assert_eq!(report.action_result_code, Some(0));
+        assert!(report.gas_used > 0);
+        assert_eq!(report.output.as_ref().map(|x| x.value), Some(42));
+
+        assert_eq!(report.out_messages.len(), 2);
+        let events: Vec<_> = report
+            .events()
+            .map(|(name, values)| (name.to_owned(), values.len()))
+            .collect();
+        assert_eq!(events, [("ValueRead".to_owned(), 0)]);
+        assert!(matches!(
+            &report.out_messages[1].decoded,
+            Some(DecodedMessage::Output { name, .. }) if name.as_ref() == "getValue"
+        ));
+
+        // the getter accepts, so gas is charged and the lt moves past the last transaction
+        let fee = u128::from(report.gas_used) * u128::from(tvm::DEFAULT_GAS_PRICE);
+        assert_eq!(
+            report.account.balance().into_inner(),
+            contract.balance().into_inner() - fee
+        );
+        assert_eq!(report.account.last_transaction(), (43, None));
+    }
+
+    #[test]
+    fn run_local_decodes_the_getter_output() {
+        let output = getter_contract(7)
+            .run_local::<getter::functions::GetValue>(
+                getter::GetValueFunctionInput,
+                &ExecutionContext::default(),
+            )
+            .unwrap();
+        assert_eq!(output.value, 7);
+    }
+
+    fn failed_report(
+        exit_code: i32,
+        action_result_code: Option<i32>,
+    ) -> ExecutionReport<getter::GetValueFunctionOutput> {
+        ExecutionReport {
+            exit_code,
+            action_result_code,
+            gas_used: 0,
+            vm_steps: 0,
+            output: None,
+            out_messages: Vec::new(),
+            account: exact_contract(),
+        }
+    }
+
+    #[test]
+    fn catalog_errors_are_typed() {
+        let err = into_output::<getter::functions::GetValue>(failed_report(100, None))
+            .err()
+            .expect("Exit code 100 must fail");
+        assert_eq!(
+            err.downcast_ref::<getter::GetterError>(),
+            Some(&getter::GetterError::NotOwner)
+        );
+    }
+
+    #[test]
+    fn action_result_codes_are_not_catalog_errors() {
+        let err = into_output::<getter::functions::GetValue>(failed_report(0, Some(37)))
+            .err()
+            .expect("Failed action phase must fail");
+        assert!(err.downcast_ref::<getter::GetterError>().is_none());
+    }
+
+    #[test]
+    fn offline_signature_matches_encode_external() {
+        use ed25519_dalek::{Signer, Verifier};
+
+        let key = ed25519_dalek::SigningKey::from_bytes(&[0x42; 32]);
+        let headers = getter::Headers {
+            time: Some(1_700_000_000_000),
+            expire: Some(1_700_000_060),
+            ..Default::default()
+        };
+
+        let unsigned = getter::get_value::prepare_external(
+            &getter::GetValueFunctionInput,
+            &headers,
+            Some(&key.verifying_key()),
+        )
+        .unwrap();
+        let signature = key.sign(&unsigned.hash.0);
+        key.verifying_key()
+            .verify(&unsigned.hash.0, &signature)
+            .unwrap();
+        let offline = unsigned.with_signature(&signature).unwrap();
+
+        let online = getter::get_value::encode_external(
+            &getter::GetValueFunctionInput,
+            &headers,
+            Some(&key),
+        )
+        .unwrap();
+        assert_eq!(offline.repr_hash(), online.repr_hash());
+    }
+
+    #[test]
+    fn generated_contract_matches_the_abi() {
+        use std::collections::BTreeSet;
+
+        let parsed =
+            serde_json::from_str::<Contract>(include_str!("../fixtures/getter.abi.json")).unwrap();
+        let contract = getter::contract();
+
+        assert_eq!(contract.fields, parsed.fields);
+        assert_eq!(
+            contract.functions.keys().collect::<BTreeSet<_>>(),
+            parsed.functions.keys().collect::<BTreeSet<_>>()
+        );
+        assert_eq!(
+            contract.events.keys().collect::<BTreeSet<_>>(),
+            parsed.events.keys().collect::<BTreeSet<_>>()
+        );
+    }
+}
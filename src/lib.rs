@@ -1,2 +1,10 @@
+extern crate self as abi_codegen;
+
+pub mod batch;
 pub mod contracts;
+pub mod display;
+pub mod execution;
 pub mod existing_contract;
+pub mod transport;
+
+mod tvm;
@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+use anyhow::Result;
+use everscale_types::models::message::StdAddr;
+
+use crate::existing_contract::ExistingContract;
+
+/// Source of contract states, e.g. a node, GQL or JRPC backend.
+pub trait ContractStateProvider: Send + Sync {
+    fn get_contract_state(&self, address: &StdAddr) -> Result<Option<ExistingContract>>;
+}
+
+/// Keeps contract states in memory, handy for tests.
+#[derive(Default)]
+pub struct InMemoryStateProvider {
+    states: RwLock<HashMap<StdAddr, ExistingContract>>,
+}
+
+impl InMemoryStateProvider {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&self, address: StdAddr, contract: ExistingContract) {
+        self.states.write().unwrap().insert(address, contract);
+    }
+
+    pub fn remove(&self, address: &StdAddr) -> Option<ExistingContract> {
+        self.states.write().unwrap().remove(address)
+    }
+}
+
+impl ContractStateProvider for InMemoryStateProvider {
+    fn get_contract_state(&self, address: &StdAddr) -> Result<Option<ExistingContract>> {
+        Ok(self.states.read().unwrap().get(address).cloned())
+    }
+}
+
+/// Reads contract states from `{workchain}_{address}.boc` files in a directory.
+pub struct FileSystemStateProvider {
+    root: PathBuf,
+}
+
+impl FileSystemStateProvider {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn path(&self, address: &StdAddr) -> PathBuf {
+        self.root
+            .join(format!("{}_{}.boc", address.workchain, address.address))
+    }
+
+    pub fn store(&self, address: &StdAddr, contract: &ExistingContract) -> Result<()> {
+        std::fs::create_dir_all(&self.root)?;
+        contract.save_to_file(self.path(address))
+    }
+}
+
+impl ContractStateProvider for FileSystemStateProvider {
+    fn get_contract_state(&self, address: &StdAddr) -> Result<Option<ExistingContract>> {
+        let path = self.path(address);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        ExistingContract::from_file(path).map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use everscale_types::prelude::HashBytes;
+
+    use super::*;
+    use crate::existing_contract::tests::{assert_same, exact_contract};
+
+    fn address(byte: u8) -> StdAddr {
+        StdAddr::new(0, HashBytes([byte; 32]))
+    }
+
+    #[test]
+    fn in_memory_round_trip() {
+        let provider = InMemoryStateProvider::new();
+        let contract = exact_contract();
+        provider.insert(address(0x11), contract.clone());
+
+        let loaded = provider.get_contract_state(&address(0x11)).unwrap();
+        assert_same(&contract, &loaded.unwrap());
+        assert!(provider
+            .get_contract_state(&address(0x22))
+            .unwrap()
+            .is_none());
+
+        assert!(provider.remove(&address(0x11)).is_some());
+        assert!(provider
+            .get_contract_state(&address(0x11))
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn file_system_round_trip() {
+        let root = std::env::temp_dir().join(format!("state_provider_{}", std::process::id()));
+        let provider = FileSystemStateProvider::new(&root);
+        let contract = exact_contract();
+        provider.store(&address(0x11), &contract).unwrap();
+
+        let loaded = provider.get_contract_state(&address(0x11));
+        let missing = provider.get_contract_state(&address(0x22));
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert_same(&contract, &loaded.unwrap().unwrap());
+        assert!(missing.unwrap().is_none());
+    }
+}
@@ -0,0 +1,456 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use anyhow::Result;
+use everscale_types::boc::Boc;
+use everscale_types::models::{Account, AccountState, IntAddr, MsgInfo, OwnedMessage, StateInit};
+use everscale_types::num::Tokens;
+use everscale_types::prelude::{Cell, CellBuilder, HashBytes};
+use ton_block::{Deserializable, Serializable};
+use ton_types::{SliceData, UInt256};
+use ton_vm::executor::gas::gas_state::Gas;
+use ton_vm::executor::Engine;
+use ton_vm::smart_contract_info::SmartContractInfo;
+use ton_vm::stack::savelist::SaveList;
+use ton_vm::stack::{Stack, StackItem};
+
+// selectors of the `main` entrypoint
+const INTERNAL_SELECTOR: i32 = 0;
+const EXTERNAL_SELECTOR: i32 = -1;
+
+// send message modes
+const SEND_IGNORE_ERRORS: u8 = 2;
+const SEND_CARRY_INBOUND_VALUE: u8 = 64;
+const SEND_CARRY_ALL_BALANCE: u8 = 128;
+
+// reserve currency modes
+const RESERVE_AT_MOST: u8 = 2;
+const RESERVE_PLUS_ORIGINAL: u8 = 4;
+const RESERVE_NEGATE: u8 = 8;
+
+// action phase result code when the balance can't cover the actions
+const NOT_ENOUGH_FUNDS: i32 = 37;
+
+pub(crate) const DEFAULT_GAS_LIMIT: u64 = 1_000_000;
+
+// basechain gas price in nano per gas unit
+pub(crate) const DEFAULT_GAS_PRICE: u64 = 1000;
+
+// required by recent Solidity compilers
+pub(crate) const DEFAULT_CAPABILITIES: u64 = ton_block::GlobalCapabilities::CapMycode as u64
+    | ton_block::GlobalCapabilities::CapInitCodeHash as u64
+    | ton_block::GlobalCapabilities::CapStorageFeeToTvm as u64;
+
+#[derive(Clone)]
+pub(crate) struct VmParams {
+    pub utime: u32,
+    pub lt: u64,
+    pub rand_seed: HashBytes,
+    pub capabilities: u64,
+    pub gas_limit: u64,
+    //nano per gas unit, both for the VM and for charging the balance
+    pub gas_price: u64,
+    //config params dictionary, exposed to the contract through c7
+    pub config_params: Option<ton_types::Cell>,
+    //converted codes shared between runs, code is converted on every run without it
+    pub code_cache: Option<Arc<CodeCache>>,
+}
+
+/// Contract codes converted for the VM, keyed by the code hash.
+#[derive(Default)]
+pub(crate) struct CodeCache(RwLock<HashMap<HashBytes, ton_types::Cell>>);
+
+impl CodeCache {
+    fn get_or_convert(&self, code: &Cell) -> Result<ton_types::Cell> {
+        let hash = *code.repr_hash();
+        if let Some(code) = self.0.read().unwrap().get(&hash) {
+            return Ok(code.clone());
+        }
+
+        let converted = to_ton_cell(code)?;
+        self.0.write().unwrap().insert(hash, converted.clone());
+        Ok(converted)
+    }
+}
+
+pub(crate) struct VmOutput {
+    pub exit_code: i32,
+    //`None` if the compute phase didn't commit, `Some(0)` on success
+    pub action_result_code: Option<i32>,
+    //unaccepted external messages produce no transaction, the account is left untouched
+    pub accepted: bool,
+    pub gas_used: u64,
+    pub vm_steps: u32,
+    pub out_messages: Vec<Cell>,
+    //account after the compute and action phases
+    pub account: Account,
+}
+
+/// Runs the compute phase of `account` for an inbound `message` and applies its out actions.
+///
+/// Unlike the full transaction executor this doesn't require the message to be accepted,
+/// which is what getters rely on: out messages of unaccepted externals are still collected,
+/// but the account is left as is. The action phase is approximated: gas is charged at
+/// the params price, while storage and forwarding fees are ignored.
+pub(crate) fn execute_message(
+    account: &Account,
+    message: &Cell,
+    params: &VmParams,
+) -> Result<VmOutput> {
+    let AccountState::Active(StateInit {
+        code: Some(code),
+        data: Some(data),
+        ..
+    }) = &account.state
+    else {
+        anyhow::bail!("Account is not active");
+    };
+    let code = match &params.code_cache {
+        Some(cache) => cache.get_or_convert(code)?,
+        None => to_ton_cell(code)?,
+    };
+    let data = to_ton_cell(data)?;
+    let address = to_ton_address(&account.address)?;
+    let message = ton_block::Message::construct_from_cell(to_ton_cell(message)?)?;
+
+    let balance = account.balance.tokens.into_inner();
+    let (message_value, selector) = match message.int_header() {
+        Some(header) => (header.value.grams.as_u128(), INTERNAL_SELECTOR),
+        None => (0, EXTERNAL_SELECTOR),
+    };
+
+    let mut stack = Stack::new();
+    stack
+        .push(StackItem::int(balance))
+        .push(StackItem::int(message_value))
+        .push(StackItem::Cell(message.serialize()?))
+        .push(StackItem::Slice(message.body().unwrap_or_default()))
+        .push(StackItem::int(selector));
+
+    let mut smc_info = SmartContractInfo::with_myself(SliceData::load_cell(address.serialize()?)?);
+    *smc_info.unix_time_mut() = params.utime;
+    *smc_info.block_lt_mut() = params.lt;
+    *smc_info.trans_lt_mut() = params.lt;
+    *smc_info.balance_remaining_grams_mut() = balance;
+    smc_info.calc_rand_seed(
+        UInt256::from(params.rand_seed.0),
+        &address.address().get_bytestring(0),
+    );
+    if let Some(config_params) = &params.config_params {
+        smc_info.set_config_params(Some(config_params.clone()));
+    }
+
+    let mut ctrls = SaveList::new();
+    ctrls.put(4, &mut StackItem::Cell(data))?;
+    ctrls.put(7, &mut smc_info.into_temp_data_item())?;
+
+    let gas_limit = params.gas_limit as i64;
+    let gas_price = params.gas_price as i64;
+    // externals run on credit until they accept, like in the transaction executor
+    let gas = if selector == EXTERNAL_SELECTOR {
+        Gas::new(0, gas_limit, gas_limit, gas_price)
+    } else {
+        Gas::new(gas_limit, 0, gas_limit, gas_price)
+    };
+
+    let mut engine = Engine::with_capabilities(params.capabilities).setup_with_libraries(
+        SliceData::load_cell(code)?,
+        Some(ctrls),
+        Some(stack),
+        Some(gas),
+        Vec::new(),
+    );
+
+    let exit_code = match engine.execute() {
+        Ok(exit_code) => exit_code,
+        Err(err) => ton_vm::error::tvm_exception_or_custom_code(&err),
+    };
+    let gas_used = engine.gas_used() as u64;
+    let accepted = selector == INTERNAL_SELECTOR || engine.get_gas().get_gas_credit() == 0;
+
+    let mut new_account = account.clone();
+    if accepted {
+        let fee = u128::from(gas_used) * u128::from(params.gas_price);
+        new_account.last_trans_lt = params.lt;
+        new_account.balance.tokens = Tokens::new(balance.saturating_sub(fee));
+    }
+
+    let mut out_messages = Vec::new();
+    let mut action_result_code = None;
+
+    let committed = engine.get_committed_state();
+    if committed.is_committed() {
+        let actions =
+            ton_block::OutActions::construct_from_cell(committed.get_actions().as_cell()?.clone())?;
+        let phase = ActionPhase::new(
+            address,
+            // original balance excludes the credited message value
+            balance.saturating_sub(message_value),
+            new_account.balance.tokens.into_inner(),
+            message_value,
+        );
+
+        // failed action phase rolls back everything but the compute fees
+        match phase.apply(actions)? {
+            Some(phase) => {
+                if accepted {
+                    if let AccountState::Active(state) = &mut new_account.state {
+                        state.data = Some(from_ton_cell(committed.get_root().as_cell()?)?);
+                        if let Some(code) = phase.new_code {
+                            state.code = Some(code);
+                        }
+                    }
+                    new_account.balance.tokens = Tokens::new(phase.available + phase.reserved);
+                }
+                out_messages = phase.out_messages;
+                action_result_code = Some(0);
+            }
+            None => action_result_code = Some(NOT_ENOUGH_FUNDS),
+        }
+    }
+
+    Ok(VmOutput {
+        exit_code,
+        action_result_code,
+        accepted,
+        gas_used,
+        vm_steps: engine.steps(),
+        out_messages,
+        account: new_account,
+    })
+}
+
+struct ActionPhase {
+    //source of the sent messages
+    address: ton_block::MsgAddressInt,
+    //balance before the compute phase, without the message value
+    original: u128,
+    //balance left for sending
+    available: u128,
+    reserved: u128,
+    message_value: u128,
+    new_code: Option<Cell>,
+    out_messages: Vec<Cell>,
+}
+
+impl ActionPhase {
+    fn new(
+        address: ton_block::MsgAddressInt,
+        original: u128,
+        available: u128,
+        message_value: u128,
+    ) -> Self {
+        Self {
+            address,
+            original,
+            available,
+            reserved: 0,
+            message_value,
+            new_code: None,
+            out_messages: Vec::new(),
+        }
+    }
+
+    // `None` if the balance doesn't cover the actions
+    fn apply(mut self, actions: ton_block::OutActions) -> Result<Option<Self>> {
+        for action in actions {
+            match action {
+                ton_block::OutAction::SendMsg { mode, mut out_msg } => {
+                    out_msg.set_src_address(self.address.clone());
+                    let mut message =
+                        from_ton_cell(&out_msg.serialize()?)?.parse::<OwnedMessage>()?;
+                    if let MsgInfo::Int(info) = &mut message.info {
+                        let value = if mode & SEND_CARRY_ALL_BALANCE != 0 {
+                            self.available
+                        } else if mode & SEND_CARRY_INBOUND_VALUE != 0 {
+                            info.value.tokens.into_inner() + self.message_value
+                        } else {
+                            info.value.tokens.into_inner()
+                        };
+
+                        if value > self.available {
+                            if mode & SEND_IGNORE_ERRORS != 0 {
+                                continue;
+                            }
+                            return Ok(None);
+                        }
+                        self.available -= value;
+                        info.value.tokens = Tokens::new(value);
+                    }
+                    self.out_messages.push(CellBuilder::build_from(&message)?);
+                }
+                ton_block::OutAction::ReserveCurrency { mode, value } => {
+                    let mut amount = value.grams.as_u128();
+                    if mode & RESERVE_PLUS_ORIGINAL != 0 {
+                        amount = if mode & RESERVE_NEGATE != 0 {
+                            self.original.saturating_sub(amount)
+                        } else {
+                            self.original + amount
+                        };
+                    }
+                    if mode & RESERVE_AT_MOST != 0 {
+                        amount = amount.min(self.available);
+                    } else if amount > self.available {
+                        return Ok(None);
+                    }
+                    self.available -= amount;
+                    self.reserved += amount;
+                }
+                ton_block::OutAction::SetCode { new_code } => {
+                    self.new_code = Some(from_ton_cell(&new_code)?);
+                }
+                _ => {}
+            }
+        }
+        Ok(Some(self))
+    }
+}
+
+fn to_ton_address(address: &IntAddr) -> Result<ton_block::MsgAddressInt> {
+    ton_block::MsgAddressInt::construct_from_cell(to_ton_cell(&CellBuilder::build_from(address)?)?)
+}
+
+pub(crate) fn to_ton_cell(cell: &Cell) -> Result<ton_types::Cell> {
+    ton_types::deserialize_tree_of_cells(&mut Boc::encode(cell).as_slice())
+}
+
+fn from_ton_cell(cell: &ton_types::Cell) -> Result<Cell> {
+    Ok(Boc::decode(ton_types::serialize_toc(cell)?)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use everscale_types::cell::CellSliceRange;
+    use everscale_types::models::{CurrencyCollection, IntMsgInfo, StdAddr};
+
+    use super::*;
+
+    const BALANCE: u128 = 1000;
+    const MESSAGE_VALUE: u128 = 300;
+
+    // account credited with the inbound message value
+    fn phase() -> ActionPhase {
+        let address = IntAddr::Std(StdAddr::new(0, HashBytes([0x11; 32])));
+        ActionPhase::new(
+            to_ton_address(&address).unwrap(),
+            BALANCE - MESSAGE_VALUE,
+            BALANCE,
+            MESSAGE_VALUE,
+        )
+    }
+
+    fn send(mode: u8, value: u128) -> ton_block::OutAction {
+        let body = Cell::empty_cell();
+        let range = CellSliceRange::full(body.as_ref());
+        let message = OwnedMessage {
+            info: MsgInfo::Int(IntMsgInfo {
+                dst: IntAddr::Std(StdAddr::new(0, HashBytes([0x33; 32]))),
+                value: CurrencyCollection::new(value),
+                ..Default::default()
+            }),
+            init: None,
+            body: (body, range),
+            layout: None,
+        };
+        let cell = to_ton_cell(&CellBuilder::build_from(&message).unwrap()).unwrap();
+
+        ton_block::OutAction::SendMsg {
+            mode,
+            out_msg: ton_block::Message::construct_from_cell(cell).unwrap(),
+        }
+    }
+
+    fn reserve(mode: u8, value: u64) -> ton_block::OutAction {
+        ton_block::OutAction::ReserveCurrency {
+            mode,
+            value: ton_block::CurrencyCollection::with_grams(value),
+        }
+    }
+
+    fn apply(actions: Vec<ton_block::OutAction>) -> Option<ActionPhase> {
+        phase().apply(actions.into_iter().collect()).unwrap()
+    }
+
+    fn sent_values(phase: &ActionPhase) -> Vec<u128> {
+        phase
+            .out_messages
+            .iter()
+            .map(
+                |message| match message.parse::<OwnedMessage>().unwrap().info {
+                    MsgInfo::Int(info) => info.value.tokens.into_inner(),
+                    _ => panic!("Expected an internal message"),
+                },
+            )
+            .collect()
+    }
+
+    #[test]
+    fn plain_send_deducts_value() {
+        let phase = apply(vec![send(0, 100)]).unwrap();
+        assert_eq!(sent_values(&phase), [100]);
+        assert_eq!(phase.available, 900);
+    }
+
+    #[test]
+    fn carry_inbound_value() {
+        let phase = apply(vec![send(SEND_CARRY_INBOUND_VALUE, 100)]).unwrap();
+        assert_eq!(sent_values(&phase), [400]);
+        assert_eq!(phase.available, 600);
+    }
+
+    #[test]
+    fn carry_all_balance() {
+        let phase = apply(vec![send(0, 100), send(SEND_CARRY_ALL_BALANCE, 0)]).unwrap();
+        assert_eq!(sent_values(&phase), [100, 900]);
+        assert_eq!(phase.available, 0);
+    }
+
+    #[test]
+    fn ignore_errors_skips_unpayable_messages() {
+        let phase = apply(vec![send(SEND_IGNORE_ERRORS, 2000), send(0, 100)]).unwrap();
+        assert_eq!(sent_values(&phase), [100]);
+        assert_eq!(phase.available, 900);
+    }
+
+    #[test]
+    fn reserve_at_most() {
+        let phase = apply(vec![reserve(RESERVE_AT_MOST, 5000)]).unwrap();
+        assert_eq!(phase.reserved, BALANCE);
+        assert_eq!(phase.available, 0);
+    }
+
+    #[test]
+    fn reserve_plus_original() {
+        let phase = apply(vec![
+            reserve(RESERVE_PLUS_ORIGINAL, 100),
+            send(SEND_CARRY_ALL_BALANCE, 0),
+        ])
+        .unwrap();
+        assert_eq!(phase.reserved, 800);
+        assert_eq!(sent_values(&phase), [200]);
+    }
+
+    #[test]
+    fn reserve_negate() {
+        let phase = apply(vec![reserve(RESERVE_PLUS_ORIGINAL | RESERVE_NEGATE, 100)]).unwrap();
+        assert_eq!(phase.reserved, 600);
+        assert_eq!(phase.available, 400);
+    }
+
+    #[test]
+    fn set_code_replaces_code() {
+        let code = CellBuilder::build_from(0xdeadbeefu32).unwrap();
+        let phase = apply(vec![ton_block::OutAction::SetCode {
+            new_code: to_ton_cell(&code).unwrap(),
+        }])
+        .unwrap();
+        assert_eq!(phase.new_code, Some(code));
+    }
+
+    #[test]
+    fn insufficient_funds_fail_the_phase() {
+        assert!(apply(vec![send(0, 100), send(0, 2000)]).is_none());
+        assert!(apply(vec![reserve(0, 2000)]).is_none());
+        assert!(apply(vec![reserve(0, 800), send(0, 300)]).is_none());
+    }
+}